@@ -0,0 +1,185 @@
+//! Expected answers are kept as data in `answers/<account>.toml`, one file per set of puzzle
+//! inputs, so a new account can be checked without touching any day.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use crate::{parser::InputSource, runner::SOLVERS};
+
+pub struct Account {
+    pub name: String,
+    pub source: InputSource,
+    answers: BTreeMap<(usize, usize), String>,
+}
+
+impl Account {
+    /// Parse an account's answers. `input` is resolved against `root`.
+    pub fn parse(name: &str, text: &str, root: &Path) -> Result<Self, String> {
+        let table = text
+            .parse::<toml::Table>()
+            .map_err(|e| format!("{}: {}", name, e))?;
+
+        let input = table
+            .get("input")
+            .and_then(|i| i.as_str())
+            .ok_or_else(|| format!("{}: expected `input` directory", name))?;
+        let mut source = InputSource::directory(root.join(input));
+        if let Some(year) = table.get("year") {
+            let year = year
+                .as_integer()
+                .and_then(|y| u32::try_from(y).ok())
+                .ok_or_else(|| format!("{}: expected `year` to be a year, not {}", name, year))?;
+            source = source.year(year);
+        }
+
+        let mut answers = BTreeMap::new();
+        for (key, value) in &table {
+            let Some(day) = key.strip_prefix("day").and_then(|d| d.parse().ok()) else {
+                continue;
+            };
+            let parts = value
+                .as_table()
+                .ok_or_else(|| format!("{}: expected [{}] to be a table", name, key))?;
+            for (part, answer) in parts {
+                let part = part
+                    .strip_prefix("part")
+                    .and_then(|p| p.parse().ok())
+                    .ok_or_else(|| format!("{}: unexpected key {}.{}", name, key, part))?;
+                let answer = match answer {
+                    toml::Value::String(s) => s.clone(),
+                    toml::Value::Integer(i) => i.to_string(),
+                    _ => return Err(format!("{}: {}.part{} must be a number", name, key, part)),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            source,
+            answers,
+        })
+    }
+
+    pub fn expected(&self, day: usize, part: usize) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|a| a.as_str())
+    }
+}
+
+fn repository() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Every account with a file in `answers/`, in name order.
+pub fn accounts() -> Vec<Account> {
+    let mut paths = fs::read_dir(repository().join("answers"))
+        .expect("Unable to read answers directory")
+        .map(|entry| entry.expect("Unable to read answers entry").path())
+        .filter(|path| path.extension().is_some_and(|e| e == "toml"))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy();
+            let text = fs::read_to_string(path).expect("Unable to read answers");
+            Account::parse(&name, &text, &repository()).unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_account() {
+        let account = Account::parse(
+            "example",
+            "input = \"inputs/example\"\nyear = 2022\n\n[day3]\npart1 = 4361\npart2 = \"467835\"",
+            Path::new("/repo"),
+        )
+        .unwrap();
+        assert_eq!(
+            account.source.path(3),
+            Some(PathBuf::from("/repo/inputs/example/2022/day3.txt"))
+        );
+        assert_eq!(account.expected(3, 1), Some("4361"));
+        assert_eq!(account.expected(3, 2), Some("467835"));
+        assert_eq!(account.expected(4, 1), None);
+        assert!(Account::parse("example", "[day1]\npart1 = 1", Path::new("/repo")).is_err());
+        assert!(Account::parse(
+            "example",
+            "input = \"i\"\n[day1]\nfirst = 1",
+            Path::new("/")
+        )
+        .is_err());
+        for year in ["-2023", "4294967296", "\"2023\""] {
+            let text = format!("input = \"i\"\nyear = {}", year);
+            assert_eq!(
+                Account::parse("example", &text, Path::new("/")).err(),
+                Some(format!(
+                    "example: expected `year` to be a year, not {}",
+                    year
+                ))
+            );
+        }
+    }
+
+    /// Check every registered solver against every account which has an answer for it.
+    #[test]
+    fn registered_solvers() {
+        let accounts = accounts();
+        assert!(!accounts.is_empty(), "No accounts found in answers/");
+
+        let mut failures = Vec::new();
+        for account in &accounts {
+            for (day, part) in account.answers.keys() {
+                if !SOLVERS.iter().any(|s| s.day == *day && s.part == *part) {
+                    failures.push(format!(
+                        "{}: no solver for day {} part {}",
+                        account.name, day, part
+                    ));
+                }
+            }
+
+            thread::scope(|scope| {
+                let results = SOLVERS
+                    .iter()
+                    .filter_map(|solver| {
+                        let expected = account.expected(solver.day, solver.part)?;
+                        let result = scope.spawn(|| {
+                            let input =
+                                account.source.read(solver.day).map_err(|e| e.to_string())?;
+                            solver
+                                .solve(&input)
+                                .map(|answer| answer.to_string())
+                                .map_err(|e| e.to_string())
+                        });
+                        Some((solver, expected, result))
+                    })
+                    .collect::<Vec<_>>();
+
+                for (solver, expected, result) in results {
+                    let failure = match result.join() {
+                        Ok(Ok(answer)) if answer == expected => continue,
+                        Ok(Ok(answer)) => format!("expected {} but got {}", expected, answer),
+                        Ok(Err(e)) => e,
+                        Err(_) => "panicked".to_string(),
+                    };
+                    failures.push(format!(
+                        "{}: day {} part {}: {}",
+                        account.name, solver.day, solver.part, failure
+                    ));
+                }
+            });
+        }
+
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+}
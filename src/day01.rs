@@ -1,112 +1,114 @@
-use std::collections::HashMap;
+use crate::parser::{self, ParseError};
 
-use crate::parser;
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
 
-#[aoc_generator(day1)]
-pub fn parse(input: &str) -> Vec<String> {
-    parser::test_input(input)
+/// The digits found on a line of the calibration document, in order.
+#[derive(Debug)]
+pub struct Calibration {
+    /// Only those written as numerals
+    numerals: Vec<u32>,
+    /// Numerals along with those spelled out, which may overlap, e.g. "twone"
+    spelled: Vec<u32>,
 }
 
-#[aoc(day1, part1)]
-pub fn part1(input: &Vec<String>) -> u32 {
-    let lookup_1 = HashMap::from([
-        ("1", 1),
-        ("2", 2),
-        ("3", 3),
-        ("4", 4),
-        ("5", 5),
-        ("6", 6),
-        ("7", 7),
-        ("8", 8),
-        ("9", 9),
-    ]);
-    solve(input, lookup_1)
+impl Calibration {
+    fn new(text: &str) -> Self {
+        let mut numerals = Vec::new();
+        let mut spelled = Vec::new();
+        for index in 0..text.len() {
+            let rest = &text[index..];
+            if let Some(digit) = rest.chars().next().and_then(|c| c.to_digit(10)) {
+                numerals.push(digit);
+                spelled.push(digit);
+            } else if let Some(word) = WORDS.iter().position(|w| rest.starts_with(w)) {
+                spelled.push(word as u32 + 1);
+            }
+        }
+        Self { numerals, spelled }
+    }
 }
 
-#[aoc(day1, part2)]
-pub fn part2(input: &Vec<String>) -> u32 {
-    let lookup_2 = HashMap::from([
-        ("1", 1),
-        ("2", 2),
-        ("3", 3),
-        ("4", 4),
-        ("5", 5),
-        ("6", 6),
-        ("7", 7),
-        ("8", 8),
-        ("9", 9),
-        ("one", 1),
-        ("two", 2),
-        ("three", 3),
-        ("four", 4),
-        ("five", 5),
-        ("six", 6),
-        ("seven", 7),
-        ("eight", 8),
-        ("nine", 9),
-    ]);
-    solve(input, lookup_2)
+/// The first and last digits combined into a two digit number, or 0 if there are none.
+fn value(digits: &[u32]) -> u32 {
+    match (digits.first(), digits.last()) {
+        (Some(first), Some(last)) => first * 10 + last,
+        _ => 0,
+    }
 }
 
-fn solve(input: &Vec<String>, lookup: HashMap<&str, i32>) -> u32 {
-    let mut sum = 0;
-    for line in input {
-        let mut digits = Vec::new();
-
-        for index in 0..line.len() {
-            for entry in lookup.keys() {
-                if line.len() >= index + entry.len() && &&line[index..index + entry.len()] == entry
-                {
-                    digits.push(lookup.get(entry).unwrap());
-                }
+#[aoc_generator(day1)]
+pub fn parse(input: &str) -> Result<Vec<Calibration>, ParseError> {
+    parser::lines(1, input)
+        .map(|line| {
+            if let Some(index) = line.text.find(|c: char| !c.is_ascii_alphanumeric()) {
+                return Err(line.error_at(index, "expected a letter or digit"));
             }
-        }
+            let calibration = Calibration::new(line.text);
+            if calibration.spelled.is_empty() {
+                return Err(line.error(line.text, "expected a digit"));
+            }
+            Ok(calibration)
+        })
+        .collect()
+}
 
-        sum += format!("{}{}", digits.first().unwrap(), digits.last().unwrap())
-            .parse::<u32>()
-            .unwrap();
-    }
-    sum
+/// Lines with only spelled out digits have no calibration value here, so count as 0.
+#[aoc(day1, part1)]
+pub fn part1(input: &[Calibration]) -> u32 {
+    input.iter().map(|c| value(&c.numerals)).sum()
+}
+
+#[aoc(day1, part2)]
+pub fn part2(input: &[Calibration]) -> u32 {
+    input.iter().map(|c| value(&c.spelled)).sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use test_case::test_case;
 
     #[test]
     fn part1_test() {
-        assert_eq!(
-            part1(&vec![
-                "1abc2".to_string(),
-                "pqr3stu8vwx".to_string(),
-                "a1b2c3d4e5f".to_string(),
-                "treb7uchet".to_string()
-            ]),
-            142
+        let input = parse(
+            "1abc2
+            pqr3stu8vwx
+            a1b2c3d4e5f
+            treb7uchet",
         )
+        .unwrap();
+        assert_eq!(part1(&input), 142);
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(
-            part2(&parser::test_input(
-                "nqninenmvnpsz874
-                8twofpmpxkvvdnpdnlpkhseven4ncgkb
-                six8shdkdcdgseven8xczqrnnmthreecckfive
-                qlcnz54dd75nine7jfnlfgz
-                7vrdhggdkqbnltlgpkkvsdxn2mfpghkntzrhtjgtxr
-                cdhmktwo6kjqbprvfour8
-                ninekkvkeight9three",
-            )),
-            493
+        let input = parse(
+            "nqninenmvnpsz874
+            8twofpmpxkvvdnpdnlpkhseven4ncgkb
+            six8shdkdcdgseven8xczqrnnmthreecckfive
+            qlcnz54dd75nine7jfnlfgz
+            7vrdhggdkqbnltlgpkkvsdxn2mfpghkntzrhtjgtxr
+            cdhmktwo6kjqbprvfour8
+            ninekkvkeight9three",
         )
+        .unwrap();
+        assert_eq!(part2(&input), 493);
     }
 
     #[test]
-    fn mainline() {
-        let input = parser::load_input(1);
-        assert_eq!(part1(&input), 56397);
-        assert_eq!(part2(&input), 55701);
+    fn spelled_only() {
+        let input = parse("eightwothree\n7pqrstsixteen").unwrap();
+        assert_eq!(part1(&input), 77);
+        assert_eq!(part2(&input), 83 + 76);
+    }
+
+    #[test_case("1abc2\npqrstuvwx", 2, 1, "pqrstuvwx", "expected a digit" ; "no digits")]
+    #[test_case("1abc2\npqr3stu 8vwx", 2, 8, " ", "expected a letter or digit" ; "space")]
+    #[test_case("1abc2\npqr3-8", 2, 5, "-", "expected a letter or digit" ; "symbol")]
+    fn parse_error(input: &str, line: usize, column: usize, token: &str, reason: &str) {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err, ParseError::new(1, line, column, token, reason));
     }
 }
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use crate::parser;
+use crate::parser::{self, ParseError};
 
+#[derive(Debug)]
 pub struct Game {
     id: usize,
     red: usize,
@@ -10,38 +11,42 @@ pub struct Game {
 }
 
 #[aoc_generator(day2)]
-pub fn parse(input: &str) -> Vec<Game> {
-    let input = parser::test_input(input);
+pub fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
     let mut games = Vec::new();
 
-    for (index, game) in input.iter().enumerate() {
+    for (index, line) in parser::lines(2, input).enumerate() {
         let mut required_colours = HashMap::from([("red", 0), ("green", 0), ("blue", 0)]);
-        let draws = game.split([':', ';']).collect::<Vec<&str>>();
-        for draw in draws {
-            if draw.contains("Game") {
-                continue;
-            }
-            for result in draw.split(',').collect::<Vec<&str>>() {
-                let number_colour = result.trim().split(' ').collect::<Vec<&str>>();
+        let draws = line
+            .text
+            .split_once(':')
+            .ok_or_else(|| line.missing("expected ':' after the game id"))?
+            .1;
+        for draw in draws.split(';') {
+            for result in draw.split(',') {
+                let mut number_colour = result.split_whitespace();
                 let number = number_colour
-                    .first()
-                    .expect("Failed to extract number of colour")
-                    .parse::<usize>()
-                    .expect("Failed to parse number of colour");
-                let colour = number_colour.get(1).expect("Failed to extract colour");
-                if *required_colours.get(colour).unwrap() < number {
-                    required_colours.insert(colour, number);
+                    .next()
+                    .ok_or_else(|| line.error(result, "expected number of colour"))?;
+                let number = line.parse::<usize>(number, "expected number of colour")?;
+                let colour = number_colour
+                    .next()
+                    .ok_or_else(|| line.error(result, "expected colour"))?;
+                let required = required_colours
+                    .get_mut(colour)
+                    .ok_or_else(|| line.error(colour, "unknown colour"))?;
+                if *required < number {
+                    *required = number;
                 }
             }
         }
         games.push(Game {
             id: index + 1,
-            red: *required_colours.get("red").unwrap(),
-            green: *required_colours.get("green").unwrap(),
-            blue: *required_colours.get("blue").unwrap(),
+            red: required_colours["red"],
+            green: required_colours["green"],
+            blue: required_colours["blue"],
         });
     }
-    games
+    Ok(games)
 }
 
 #[aoc(day2, part1)]
@@ -67,7 +72,6 @@ pub fn part2(games: &Vec<Game>) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
 
     const TEST: &str = "
             Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
@@ -78,19 +82,25 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(&parse(TEST)), 8);
+        assert_eq!(part1(&parse(TEST).unwrap()), 8);
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(&parse(TEST)), 2286);
+        assert_eq!(part2(&parse(TEST).unwrap()), 2286);
     }
 
     #[test]
-    fn mainline() {
-        let input = parser::load_input_string(2);
-        let games = parse(&input);
-        assert_eq!(part1(&games), 2278);
-        assert_eq!(part2(&games), 67953);
+    fn parse_error() {
+        let err = parse("Game 1: 3 blue, 4 red\nGame 2: 1 blue; x green").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(2, 2, 17, "x", "expected number of colour")
+        );
+        let err = parse("Game 1: 3 blue, 4 purple").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.token.as_str()),
+            (1, 19, "purple")
+        );
     }
 }
@@ -1,65 +1,55 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
+use crate::{
+    grid::Grid,
+    parser::{Line, ParseError},
+};
+
+#[derive(Clone, Debug)]
 pub enum Point {
     Period,
     Symbol(char),
     Part(u32),
 }
 
-type Schematic = HashMap<(usize, usize), Point>;
+type Schematic = Grid<Point>;
 
 #[aoc_generator(day3)]
-pub fn parse(input: &str) -> Schematic {
-    let mut schematic = HashMap::new();
-
-    // Populate schematic
-    for (row, line) in input
-        .trim()
-        .lines()
-        .map(|l| l.trim().to_string())
-        .enumerate()
-    {
-        let mut part = Vec::new();
-        for (col, c) in line.chars().enumerate() {
-            match c {
-                '.' => {
-                    if !part.is_empty() {
-                        process_part(&mut schematic, row, col - 1, &mut part);
-                    }
-                    schematic.insert((row, col), Point::Period);
-                }
-                _ => {
-                    if c.is_numeric() {
-                        part.push(c);
-                    } else {
-                        if !part.is_empty() {
-                            process_part(&mut schematic, row, col - 1, &mut part);
-                        }
-                        schematic.insert((row, col), Point::Symbol(c));
-                    }
-                }
+pub fn parse(input: &str) -> Result<Schematic, ParseError> {
+    Grid::parse_rows(3, input, |line| {
+        let mut row = Vec::new();
+        let mut part_start = None;
+        for (col, c) in line.text.char_indices() {
+            if c.is_numeric() {
+                part_start.get_or_insert(col);
+                continue;
             }
+            if let Some(start) = part_start.take() {
+                process_part(&mut row, line, start, col)?;
+            }
+            match c {
+                '.' => row.push(Point::Period),
+                _ => row.push(Point::Symbol(c)),
+            };
         }
 
         // Handle number at the end of a row
-        if !part.is_empty() {
-            process_part(&mut schematic, row, line.len() - 1, &mut part);
+        if let Some(start) = part_start {
+            process_part(&mut row, line, start, line.text.len())?;
         }
-    }
-    schematic
+        Ok(row)
+    })
 }
 
-fn process_part(schematic: &mut Schematic, row: usize, col: usize, part: &mut Vec<char>) {
-    let number = part
-        .iter()
-        .collect::<String>()
-        .parse::<u32>()
-        .expect("Unable to parse part number");
-
-    for i in 0..part.len() {
-        schematic.insert((row, col - i), Point::Part(number));
-    }
-    part.clear();
+fn process_part(
+    row: &mut Vec<Point>,
+    line: &Line,
+    start: usize,
+    end: usize,
+) -> Result<(), ParseError> {
+    let number = line.parse::<u32>(&line.text[start..end], "unable to parse part number")?;
+    row.extend((start..end).map(|_| Point::Part(number)));
+    Ok(())
 }
 
 #[aoc(day3, part1)]
@@ -76,14 +66,12 @@ fn solve(schematic: &Schematic) -> (u32, u32) {
     // Find symbols in the schematic and sum adjacent parts
     let mut part_1_parts = Vec::new();
     let mut part_2_sum = 0;
-    for ((row, col), point) in schematic {
+    for (pos, point) in schematic.iter() {
         if let Point::Symbol(s) = point {
             let mut adjacent_parts = HashSet::new();
-            for r in row - 1..row + 2 {
-                for c in col - 1..col + 2 {
-                    if let Some(Point::Part(n)) = schematic.get(&(r, c)) {
-                        adjacent_parts.insert(*n);
-                    }
+            for neighbour in schematic.neighbours8(pos) {
+                if let Point::Part(n) = schematic[neighbour] {
+                    adjacent_parts.insert(n);
                 }
             }
             for part in &adjacent_parts {
@@ -100,12 +88,12 @@ fn solve(schematic: &Schematic) -> (u32, u32) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
 
     #[test]
     fn both_test() {
-        let result = solve(&parse(
-            "467..114..
+        let result = solve(
+            &parse(
+                "467..114..
             ...*......
             ..35..633.
             ......#...
@@ -115,15 +103,19 @@ mod tests {
             ......755.
             ...$.*....
             .664.598..",
-        ));
+            )
+            .unwrap(),
+        );
         assert_eq!(result.0, 4361);
         assert_eq!(result.1, 467835);
     }
 
     #[test]
-    fn mainline() {
-        let result = solve(&parse(&parser::load_input_string(3)));
-        assert_eq!(result.0, 532331);
-        assert_eq!(result.1, 82301120);
+    fn parse_error() {
+        let err = parse("467..114..\n...*......\n..35..99999999999.").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(3, 3, 7, "99999999999", "unable to parse part number")
+        );
     }
 }
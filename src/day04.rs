@@ -1,29 +1,34 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::parser::{self, ParseError};
+
 #[aoc_generator(day4)]
-pub fn parse(input: &str) -> HashMap<usize, usize> {
+pub fn parse(input: &str) -> Result<HashMap<usize, usize>, ParseError> {
     let mut results = HashMap::new();
 
-    for (index, game) in input
-        .trim()
-        .lines()
-        .map(|l| l.trim().to_string())
-        .enumerate()
-    {
-        let segments = game.split([':', '|']).collect::<Vec<&str>>();
-        let winners = segments[1].split_whitespace().collect::<Vec<&str>>();
-        let picks: HashSet<&str> =
-            HashSet::from_iter(segments[2].split_whitespace().collect::<Vec<&str>>());
+    for (index, line) in parser::lines(4, input).enumerate() {
+        let (_, numbers) = line
+            .text
+            .split_once(':')
+            .ok_or_else(|| line.missing("expected ':' after the card id"))?;
+        let (winners, picks) = numbers
+            .split_once('|')
+            .ok_or_else(|| line.missing("expected '|' between winners and picks"))?;
+        let picks = picks
+            .split_whitespace()
+            .map(|pick| line.parse::<u32>(pick, "expected a picked number"))
+            .collect::<Result<HashSet<u32>, ParseError>>()?;
 
         let mut matches: usize = 0;
-        for winner in winners {
-            if picks.contains(winner) {
+        for winner in winners.split_whitespace() {
+            let winner = line.parse::<u32>(winner, "expected a winning number")?;
+            if picks.contains(&winner) {
                 matches += 1;
             }
         }
         results.insert(index + 1, matches);
     }
-    results
+    Ok(results)
 }
 
 #[aoc(day4, part1)]
@@ -62,7 +67,6 @@ pub fn part2(results: &HashMap<usize, usize>) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
 
     const TEST: &str = "
             Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
@@ -74,18 +78,27 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(&parse(TEST)), 13);
+        assert_eq!(part1(&parse(TEST).unwrap()), 13);
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(&parse(TEST)), 30);
+        assert_eq!(part2(&parse(TEST).unwrap()), 30);
     }
 
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(4));
-        assert_eq!(part1(&input), 21558);
-        assert_eq!(part2(&input), 10425665);
+    fn parse_error() {
+        let err = parse("Card 1: 41 48 83 86 17 83 86  6 31 17").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(4, 1, 38, "", "expected '|' between winners and picks")
+        );
+        let err = parse("Card 1: 41 4b | 83").unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (12, "4b"));
+        let err = parse("Card 1: 41 48 | 83 8x 17").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(4, 1, 20, "8x", "expected a picked number")
+        );
     }
 }
@@ -1,38 +1,17 @@
-use std::cmp;
-
-enum State {
-    Seeds,
-    SeedToSoil,
-    SoilToFertilizer,
-    FertilizerToWater,
-    WaterToLight,
-    LightToTemperature,
-    TemperatureToHumidity,
-    HumidityToLocation,
-}
-
-impl State {
-    pub fn new() -> Self {
-        Self::Seeds
-    }
-
-    pub fn next(self) -> Self {
-        match self {
-            Self::Seeds => Self::SeedToSoil,
-            Self::SeedToSoil => Self::SoilToFertilizer,
-            Self::SoilToFertilizer => Self::FertilizerToWater,
-            Self::FertilizerToWater => Self::WaterToLight,
-            Self::WaterToLight => Self::LightToTemperature,
-            Self::LightToTemperature => Self::TemperatureToHumidity,
-            _ => Self::HumidityToLocation,
-        }
-    }
-}
-
-struct Mapping {
-    destination: u64,
-    source: u64,
-    range: u64,
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+};
+
+use crate::parser::{self, ParseError};
+
+/// Sends `range` values starting at `source` to the same number of values starting at `destination`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub destination: u64,
+    pub source: u64,
+    pub range: u64,
 }
 
 impl Mapping {
@@ -45,23 +24,83 @@ impl Mapping {
     }
 
     pub fn source_to_destination(&self, s: u64) -> Option<u64> {
-        if s >= self.source && s < self.source + self.range {
-            return Some(self.destination + s - self.source);
+        if s >= self.source && s < self.source_end() {
+            return self.destination.checked_add(s - self.source);
         }
         None
     }
 
+    #[cfg(test)]
     pub fn destination_to_source(&self, d: u64) -> Option<u64> {
-        if d >= self.destination && d < self.destination + self.range {
-            return Some(self.source + d - self.destination);
+        if d >= self.destination && d < self.destination_end() {
+            return self.source.checked_add(d - self.destination);
         }
         None
     }
+
+    /// Whether every source and destination, and one past the last of each, fit in a `u64`.
+    pub fn fits(&self) -> bool {
+        self.source.checked_add(self.range).is_some()
+            && self.destination.checked_add(self.range).is_some()
+    }
+
+    /// One past the last source
+    fn source_end(&self) -> u64 {
+        self.source.saturating_add(self.range)
+    }
+
+    /// One past the last destination
+    fn destination_end(&self) -> u64 {
+        self.destination.saturating_add(self.range)
+    }
+
+    fn overlaps(&self, other: &Mapping) -> bool {
+        self.source < other.source_end() && other.source < self.source_end()
+    }
+
+    /// The same mapping run backwards.
+    fn reverse(&self) -> Mapping {
+        Mapping::new(self.source, self.destination, self.range)
+    }
+
+    /// Split a range into the part this mapping covers, moved to its destination, and the
+    /// parts either side which it doesn't.
+    pub fn split(&self, seeds: &SeedRange) -> (Option<SeedRange>, Vec<SeedRange>) {
+        let start = seeds.start.max(self.source);
+        let end = seeds.end().min(self.source_end());
+        if start >= end {
+            return (None, vec![seeds.clone()]);
+        }
+
+        let mapped = SeedRange::new(self.destination + (start - self.source), end - start);
+        let outside = [
+            SeedRange::new(seeds.start, start - seeds.start),
+            SeedRange::new(end, seeds.end() - end),
+        ];
+        (
+            Some(mapped),
+            outside.into_iter().filter(|r| !r.is_empty()).collect(),
+        )
+    }
+}
+
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}..{} -> {}..{}",
+            self.source,
+            self.source_end(),
+            self.destination,
+            self.destination_end()
+        )
+    }
 }
 
-struct SeedRange {
-    start: u64,
-    range: u64,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedRange {
+    pub start: u64,
+    pub range: u64,
 }
 
 impl SeedRange {
@@ -69,11 +108,204 @@ impl SeedRange {
         Self { start, range }
     }
 
-    pub fn contains(&self, v: u64) -> bool {
-        v >= self.start && v < self.start + self.range
+    /// One past the last value in the range
+    pub fn end(&self) -> u64 {
+        self.start.saturating_add(self.range)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.range == 0
+    }
+}
+
+/// Why a set of mappings can't form a [`Stage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageError {
+    /// Both mappings claim some of the same sources
+    Overlap(Mapping, Mapping),
+    /// Both mappings send some values to the same destinations, so can't be reversed
+    NotInvertible(Mapping, Mapping),
+    /// The mapping's sources or destinations run past the largest `u64`
+    OutOfRange(Mapping),
+}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageError::Overlap(a, b) => write!(f, "mappings {} and {} overlap", a, b),
+            StageError::NotInvertible(a, b) => write!(
+                f,
+                "mappings {} and {} share destinations so can't be inverted",
+                a, b
+            ),
+            StageError::OutOfRange(m) => write!(
+                f,
+                "mapping of {} values from {} to {} runs past {}",
+                m.range,
+                m.source,
+                m.destination,
+                u64::MAX
+            ),
+        }
+    }
+}
+
+impl Error for StageError {}
+
+/// One stage of the almanac as a piecewise function over every value. The mappings are
+/// sorted by source, don't overlap and leave no gaps, with values that keep their number
+/// given explicit identity mappings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    mappings: Vec<Mapping>,
+}
+
+impl Stage {
+    /// Normalise a stage's mappings, which may be in any order but mustn't overlap.
+    pub fn new(mut mappings: Vec<Mapping>) -> Result<Self, StageError> {
+        if let Some(mapping) = mappings.iter().find(|m| !m.fits()) {
+            return Err(StageError::OutOfRange(mapping.clone()));
+        }
+        mappings.retain(|m| m.range > 0);
+        mappings.sort_by_key(|m| m.source);
+        if let Some(pair) = mappings.windows(2).find(|pair| pair[0].overlaps(&pair[1])) {
+            return Err(StageError::Overlap(pair[0].clone(), pair[1].clone()));
+        }
+
+        // Fill the gaps either side of each mapping with identity mappings
+        let mut normalised: Vec<Mapping> = Vec::new();
+        let mut next = 0;
+        for mapping in mappings
+            .into_iter()
+            .chain([Mapping::new(u64::MAX, u64::MAX, 0)])
+        {
+            if mapping.source > next {
+                push_merged(
+                    &mut normalised,
+                    Mapping::new(next, next, mapping.source - next),
+                );
+            }
+            next = mapping.source_end();
+            push_merged(&mut normalised, mapping);
+        }
+
+        Ok(Self {
+            mappings: normalised,
+        })
+    }
+
+    /// The stage which leaves every value as it is.
+    pub fn identity() -> Self {
+        Self {
+            mappings: vec![Mapping::new(0, 0, u64::MAX)],
+        }
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    pub fn get_destination(&self, source: u64) -> u64 {
+        let index = self.mappings.partition_point(|m| m.source_end() <= source);
+        self.mappings
+            .get(index)
+            .and_then(|m| m.source_to_destination(source))
+            .unwrap_or(source)
+    }
+
+    /// Map every value in the ranges, splitting them wherever a mapping starts or ends.
+    pub fn map_ranges(&self, ranges: &[SeedRange]) -> Vec<SeedRange> {
+        let mut mapped = Vec::new();
+        let mut unmapped = ranges.to_vec();
+
+        for mapping in &self.mappings {
+            let mut remaining = Vec::new();
+            for range in unmapped {
+                let (inside, outside) = mapping.split(&range);
+                mapped.extend(inside);
+                remaining.extend(outside);
+            }
+            unmapped = remaining;
+        }
+
+        mapped.extend(unmapped);
+        mapped
+    }
+
+    /// Every source which this stage sends into one of the ranges.
+    pub fn unmap_ranges(&self, ranges: &[SeedRange]) -> Vec<SeedRange> {
+        let mut sources = Vec::new();
+        for mapping in &self.mappings {
+            for range in ranges {
+                let start = range.start.max(mapping.destination);
+                let end = range.end().min(mapping.destination_end());
+                if start < end {
+                    let source = mapping.source + (start - mapping.destination);
+                    sources.push(SeedRange::new(source, end - start));
+                }
+            }
+        }
+        sources
+    }
+
+    /// A single stage doing the work of this one followed by `next`.
+    pub fn compose(&self, next: &Stage) -> Stage {
+        let mut mappings = Vec::new();
+        for mapping in &self.mappings {
+            // Split this mapping wherever its destinations cross from one of next's mappings to another
+            for then in &next.mappings {
+                let start = mapping.destination.max(then.source);
+                let end = mapping.destination_end().min(then.source_end());
+                if start < end {
+                    mappings.push(Mapping::new(
+                        then.destination + (start - then.source),
+                        mapping.source + (start - mapping.destination),
+                        end - start,
+                    ));
+                }
+            }
+        }
+        Stage::new(mappings).expect("Composed mappings come from disjoint sources")
+    }
+
+    /// The stage which undoes this one, if no two values share a destination.
+    pub fn invert(&self) -> Result<Stage, StageError> {
+        Stage::new(self.mappings.iter().map(Mapping::reverse).collect()).map_err(|e| match e {
+            StageError::Overlap(a, b) | StageError::NotInvertible(a, b) => {
+                StageError::NotInvertible(a.reverse(), b.reverse())
+            }
+            StageError::OutOfRange(m) => StageError::OutOfRange(m.reverse()),
+        })
+    }
+}
+
+/// Add a mapping to the end of a normalised list, extending the last one instead if it
+/// carries straight on from it.
+fn push_merged(mappings: &mut Vec<Mapping>, mapping: Mapping) {
+    if mapping.range == 0 {
+        return;
+    }
+    match mappings.last_mut() {
+        Some(last)
+            if last.source_end() == mapping.source
+                && last.destination_end() == mapping.destination =>
+        {
+            last.range += mapping.range
+        }
+        _ => mappings.push(mapping),
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for mapping in &self.mappings {
+            writeln!(f, "{}", mapping)?;
+        }
+        Ok(())
     }
 }
 
+#[cfg(test)]
 fn get_destination(source: u64, mappings: &Vec<Mapping>) -> u64 {
     for mapping in mappings {
         if let Some(destination) = mapping.source_to_destination(source) {
@@ -83,6 +315,7 @@ fn get_destination(source: u64, mappings: &Vec<Mapping>) -> u64 {
     source
 }
 
+#[cfg(test)]
 fn get_source(destination: u64, mappings: &Vec<Mapping>) -> u64 {
     for mapping in mappings {
         if let Some(source) = mapping.destination_to_source(destination) {
@@ -92,138 +325,244 @@ fn get_source(destination: u64, mappings: &Vec<Mapping>) -> u64 {
     destination
 }
 
-#[derive(Default)]
-pub struct Data {
+/// Why the maps in an almanac can't be followed from one category to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    /// Nothing maps from the category `at`
+    Broken {
+        from: String,
+        to: String,
+        at: String,
+    },
+    /// The maps lead back round to the category `at` without reaching `to`
+    Cycle {
+        from: String,
+        to: String,
+        at: String,
+    },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::Broken { from, to, at } => {
+                write!(f, "no map from {} on the way from {} to {}", at, from, to)
+            }
+            ChainError::Cycle { from, to, at } => write!(
+                f,
+                "maps from {} loop back to {} before reaching {}",
+                from, at, to
+            ),
+        }
+    }
+}
+
+impl Error for ChainError {}
+
+/// The seeds to be planted and every `X-to-Y map:` in the almanac, keyed by the category
+/// `X` it maps from.
+#[derive(Debug, Default)]
+pub struct Almanac {
     seeds: Vec<u64>,
-    seed_to_soil: Vec<Mapping>,
-    soil_to_fertilizer: Vec<Mapping>,
-    fertilizer_to_water: Vec<Mapping>,
-    water_to_light: Vec<Mapping>,
-    light_to_temperature: Vec<Mapping>,
-    temperature_to_humidity: Vec<Mapping>,
-    humidity_to_location: Vec<Mapping>,
+    stages: HashMap<String, (String, Stage)>,
+}
+
+impl Almanac {
+    /// The stages leading from one category to another, in the order they apply.
+    fn chain(&self, from: &str, to: &str) -> Result<Vec<&Stage>, ChainError> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::from([from]);
+        let mut category = from;
+
+        while category != to {
+            let (next, stage) = self
+                .stages
+                .get(category)
+                .ok_or_else(|| ChainError::Broken {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    at: category.to_string(),
+                })?;
+            if !visited.insert(next) {
+                return Err(ChainError::Cycle {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    at: next.to_string(),
+                });
+            }
+            chain.push(stage);
+            category = next;
+        }
+
+        Ok(chain)
+    }
+
+    /// Follow a single value through the maps, e.g. from seed 79 to its water.
+    pub fn map(&self, from: &str, value: u64, to: &str) -> Result<u64, ChainError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(value, |value, stage| stage.get_destination(value)))
+    }
+
+    /// Follow whole ranges of values through the maps.
+    pub fn map_ranges(
+        &self,
+        from: &str,
+        ranges: &[SeedRange],
+        to: &str,
+    ) -> Result<Vec<SeedRange>, ChainError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(ranges.to_vec(), |ranges, stage| stage.map_ranges(&ranges)))
+    }
+
+    /// A single stage taking values of `from` straight to `to`.
+    pub fn compose(&self, from: &str, to: &str) -> Result<Stage, ChainError> {
+        Ok(self
+            .chain(from, to)?
+            .into_iter()
+            .fold(Stage::identity(), |composed, stage| composed.compose(stage)))
+    }
+
+    /// Every value of `from` which ends up within the ranges of `to`, e.g. which seeds
+    /// produce a range of locations.
+    pub fn sources(
+        &self,
+        from: &str,
+        to: &str,
+        ranges: &[SeedRange],
+    ) -> Result<Vec<SeedRange>, ChainError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .rev()
+            .fold(ranges.to_vec(), |ranges, stage| stage.unmap_ranges(&ranges)))
+    }
 }
 
 #[aoc_generator(day5)]
-pub fn parse(input: &str) -> Data {
-    let mut res = Data::default();
-    let mut state: State = State::new();
-
-    for line in input.trim().lines().map(|l| l.trim()) {
-        // Transition to next state
-        if line.is_empty() {
-            state = state.next();
+pub fn parse(input: &str) -> Result<Almanac, ParseError> {
+    let mut res = Almanac::default();
+    let mut lines = parser::lines(5, input);
+
+    let first = lines
+        .next()
+        .ok_or_else(|| ParseError::new(5, 1, 1, "", "expected seeds"))?;
+    let (label, values) = first
+        .text
+        .split_once(':')
+        .ok_or_else(|| first.missing("expected ':' after seeds"))?;
+    if label != "seeds" {
+        return Err(first.error(label, "expected seeds"));
+    }
+    res.seeds = values
+        .split_whitespace()
+        .map(|v| first.parse::<u64>(v, "expected a number"))
+        .collect::<Result<Vec<u64>, ParseError>>()?;
+
+    // Each map's mappings as listed, keyed by the category being mapped from
+    let mut maps: HashMap<&str, (&str, Vec<Mapping>)> = HashMap::new();
+    let mut current = None;
+
+    for line in lines {
+        if line.text.is_empty() {
             continue;
         }
 
-        // Skip lines which don't contain numbers - except for the first line!
-        if let Some(c) = line.chars().next() {
-            match state {
-                State::Seeds => (),
-                _ => {
-                    if !c.is_numeric() {
-                        continue;
-                    }
-                }
+        if let Some(header) = line.text.strip_suffix(" map:") {
+            let (from, to) = header
+                .split_once("-to-")
+                .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+                .ok_or_else(|| {
+                    line.error(header, "expected a map header of the form X-to-Y map:")
+                })?;
+            if maps.insert(from, (to, Vec::new())).is_some() {
+                return Err(line.error(from, "category already has a map"));
             }
+            current = Some(from);
+            continue;
         }
 
-        // Extract numbers from line
-        let data = match state {
-            State::Seeds => line
-                .split([':'])
-                .collect::<Vec<&str>>()
-                .get(1)
-                .expect("Couldn't split seeds")
-                .split_whitespace()
-                .map(|v| v.parse::<u64>().expect("Couldn't parse seeds"))
-                .collect::<Vec<u64>>(),
-            _ => line
-                .split_whitespace()
-                .map(|v| v.parse::<u64>().expect("Couldn't parse mapping"))
-                .collect::<Vec<u64>>(),
-        };
-
-        // Find which map should be filled
-        let mapping_to_fill = match state {
-            State::Seeds => {
-                res.seeds = data.clone();
-                None
-            }
-            State::SeedToSoil => Some(&mut res.seed_to_soil),
-            State::SoilToFertilizer => Some(&mut res.soil_to_fertilizer),
-            State::FertilizerToWater => Some(&mut res.fertilizer_to_water),
-            State::WaterToLight => Some(&mut res.water_to_light),
-            State::LightToTemperature => Some(&mut res.light_to_temperature),
-            State::TemperatureToHumidity => Some(&mut res.temperature_to_humidity),
-            State::HumidityToLocation => Some(&mut res.humidity_to_location),
-        };
+        let (_, mappings) = current
+            .and_then(|from| maps.get_mut(from))
+            .ok_or_else(|| line.error(line.text, "expected a map header before mappings"))?;
+
+        let data = line
+            .text
+            .split_whitespace()
+            .map(|v| line.parse::<u64>(v, "expected a number"))
+            .collect::<Result<Vec<u64>, ParseError>>()?;
 
         // Fill the map
-        if let Some(m) = mapping_to_fill {
-            m.push(Mapping::new(data[0], data[1], data[2]));
+        match data[..] {
+            [destination, source, range] => {
+                let mapping = Mapping::new(destination, source, range);
+                if !mapping.fits() {
+                    return Err(line.error(line.text, "mapping runs past the largest value"));
+                }
+                if mappings.iter().any(|m| m.overlaps(&mapping)) {
+                    return Err(line.error(line.text, "mapping overlaps another in the same map"));
+                }
+                mappings.push(mapping);
+            }
+            _ => {
+                return Err(line.error(
+                    line.text,
+                    "expected destination, source and range for mapping",
+                ))
+            }
         }
     }
 
-    res
+    for (from, (to, mappings)) in maps {
+        let stage = Stage::new(mappings).expect("Invalid mappings are rejected above");
+        res.stages.insert(from.to_string(), (to.to_string(), stage));
+    }
+
+    Ok(res)
 }
 
 #[aoc(day5, part1)]
-pub fn part1(input: &Data) -> u64 {
-    let mut part_1_min_location = u64::MAX;
-    for seed in input.seeds.clone() {
-        let soil = get_destination(seed, &input.seed_to_soil);
-        let fertilizer = get_destination(soil, &input.soil_to_fertilizer);
-        let water = get_destination(fertilizer, &input.fertilizer_to_water);
-        let light = get_destination(water, &input.water_to_light);
-        let temperature = get_destination(light, &input.light_to_temperature);
-        let humidity = get_destination(temperature, &input.temperature_to_humidity);
-        let location = get_destination(humidity, &input.humidity_to_location);
-        part_1_min_location = cmp::min(part_1_min_location, location);
-    }
-    part_1_min_location
+pub fn part1(input: &Almanac) -> u64 {
+    input
+        .seeds
+        .iter()
+        .map(|&seed| input.map("seed", seed, "location"))
+        .collect::<Result<Vec<u64>, ChainError>>()
+        .expect("Expected the almanac to map seeds to locations")
+        .into_iter()
+        .min()
+        .unwrap_or(u64::MAX)
 }
 
 #[aoc(day5, part2)]
-pub fn part2(input: &Data) -> u64 {
-    // Given the now vast range of input seeds it's much quicker to perform the search in reverse, checking locations
-    // from 0..u64::MAX until a matching seed is found.
-    let mut seed_ranges = Vec::new();
-    let mut iter = input.seeds.iter();
-    let mut seed = iter.next();
-    let mut range = iter.next();
-
-    // Reinterpet the "seeds:" line as a range of seeds
-    while let (Some(s), Some(r)) = (seed, range) {
-        seed_ranges.push(SeedRange::new(*s, *r));
-        seed = iter.next();
-        range = iter.next();
-    }
-
-    // Work backwards from location to find the first seed which lies within one of the ranges
-    for location in 0..u64::MAX {
-        let humidity = get_source(location, &input.humidity_to_location);
-        let temperature = get_source(humidity, &input.temperature_to_humidity);
-        let light = get_source(temperature, &input.light_to_temperature);
-        let water = get_source(light, &input.water_to_light);
-        let fertilizer = get_source(water, &input.fertilizer_to_water);
-        let soil = get_source(fertilizer, &input.soil_to_fertilizer);
-        let seed = get_source(soil, &input.seed_to_soil);
-        for seed_range in &seed_ranges {
-            if seed_range.contains(seed) {
-                return location;
-            }
-        }
-    }
-
-    // Shouldn't reach
-    u64::MAX
+pub fn part2(input: &Almanac) -> u64 {
+    // Reinterpet the "seeds:" line as ranges of seeds
+    let seed_ranges = input
+        .seeds
+        .chunks_exact(2)
+        .map(|pair| SeedRange::new(pair[0], pair[1]))
+        .filter(|range| !range.is_empty())
+        .collect::<Vec<_>>();
+
+    // Carry whole ranges through a single stage from seed to location, rather than the
+    // seeds one at a time
+    input
+        .compose("seed", "location")
+        .expect("Expected the almanac to map seeds to locations")
+        .map_ranges(&seed_ranges)
+        .iter()
+        .map(|range| range.start)
+        .min()
+        .unwrap_or(u64::MAX)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use proptest::prelude::*;
 
     #[test]
     fn mapping_source_to_destination() {
@@ -276,50 +615,357 @@ mod tests {
     }
 
     #[test]
-    fn both_test() {
-        let input = parse(
-            "seeds: 79 14 55 13
-            
-            seed-to-soil map:
-            50 98 2
-            52 50 48
-            
-            soil-to-fertilizer map:
-            0 15 37
-            37 52 2
-            39 0 15
-            
-            fertilizer-to-water map:
-            49 53 8
-            0 11 42
-            42 0 7
-            57 7 4
-            
-            water-to-light map:
-            88 18 7
-            18 25 70
-           
-            light-to-temperature map:
-            45 77 23
-            81 45 19
-            68 64 13
-
-            temperature-to-humidity map:
-            0 69 1
-            1 0 69
-         
-            humidity-to-location map:
-            60 56 37
-            56 93 4",
+    fn mapping_split() {
+        let mapping = Mapping::new(50, 98, 2);
+        let (inside, outside) = mapping.split(&SeedRange::new(90, 20));
+        assert_eq!(inside, Some(SeedRange::new(50, 2)));
+        assert_eq!(
+            outside,
+            vec![SeedRange::new(90, 8), SeedRange::new(100, 10)]
+        );
+        let (inside, outside) = mapping.split(&SeedRange::new(10, 5));
+        assert_eq!(inside, None);
+        assert_eq!(outside, vec![SeedRange::new(10, 5)]);
+    }
+
+    /// Up to five mappings with sources below 130 which don't overlap, in no particular order
+    fn mappings() -> impl Strategy<Value = Vec<Mapping>> {
+        let mappings = prop::collection::vec((0..20u64, 0..30u64, 0..100u64), 0..5);
+        (mappings, any::<bool>()).prop_map(|(mappings, reverse)| {
+            let mut source = 0;
+            let mut mappings = mappings
+                .into_iter()
+                .map(|(gap, range, destination)| {
+                    source += gap;
+                    let mapping = Mapping::new(destination, source, range);
+                    source += range;
+                    mapping
+                })
+                .collect::<Vec<_>>();
+            if reverse {
+                mappings.reverse();
+            }
+            mappings
+        })
+    }
+
+    fn seed_ranges() -> impl Strategy<Value = Vec<SeedRange>> {
+        prop::collection::vec((0..120u64, 0..30u64), 1..4).prop_map(|ranges| {
+            ranges
+                .into_iter()
+                .map(|(start, range)| SeedRange::new(start, range))
+                .collect()
+        })
+    }
+
+    fn stage(mappings: Vec<Mapping>) -> Stage {
+        Stage::new(mappings).unwrap()
+    }
+
+    fn values(ranges: &[SeedRange]) -> Vec<u64> {
+        let mut values = ranges
+            .iter()
+            .flat_map(|r| r.start..r.end())
+            .collect::<Vec<_>>();
+        values.sort();
+        values
+    }
+
+    /// Every value the ranges reach, with repeats, mapped one at a time
+    fn point_wise(ranges: &[SeedRange], stages: &[Vec<Mapping>]) -> Vec<u64> {
+        let mut values = values(ranges)
+            .into_iter()
+            .map(|v| stages.iter().fold(v, get_destination))
+            .collect::<Vec<_>>();
+        values.sort();
+        values
+    }
+
+    proptest! {
+        #[test]
+        fn map_ranges_matches_point_wise(ranges in seed_ranges(), mappings in mappings()) {
+            let expected = point_wise(&ranges, std::slice::from_ref(&mappings));
+            prop_assert_eq!(values(&stage(mappings).map_ranges(&ranges)), expected);
+        }
+
+        #[test]
+        fn map_ranges_through_stages(ranges in seed_ranges(), stages in prop::collection::vec(mappings(), 1..4)) {
+            let expected = point_wise(&ranges, &stages);
+            let stages = stages.into_iter().map(stage).collect::<Vec<_>>();
+            let mapped = stages
+                .iter()
+                .fold(ranges.clone(), |ranges, stage| stage.map_ranges(&ranges));
+            prop_assert_eq!(values(&mapped), expected.clone());
+
+            let composed = stages
+                .iter()
+                .fold(Stage::identity(), |composed, stage| composed.compose(stage));
+            prop_assert_eq!(values(&composed.map_ranges(&ranges)), expected);
+        }
+
+        #[test]
+        fn normalised(mappings in mappings()) {
+            let stage = stage(mappings.clone());
+            let mut next = 0;
+            for mapping in stage.mappings() {
+                prop_assert_eq!(mapping.source, next);
+                prop_assert!(mapping.range > 0);
+                next = mapping.source_end();
+            }
+            prop_assert_eq!(next, u64::MAX);
+            for v in 0..200 {
+                prop_assert_eq!(stage.get_destination(v), get_destination(v, &mappings));
+            }
+        }
+
+        #[test]
+        fn unmap_ranges_matches_point_wise(ranges in seed_ranges(), mappings in mappings()) {
+            // Mappings never reach beyond 130, so everything larger maps to itself
+            let targets = values(&ranges).into_iter().collect::<HashSet<_>>();
+            let expected = (0..300)
+                .filter(|&v| targets.contains(&get_destination(v, &mappings)))
+                .collect::<Vec<_>>();
+
+            let mut sources = values(&stage(mappings).unmap_ranges(&ranges));
+            sources.dedup();
+            prop_assert_eq!(sources, expected);
+        }
+
+        #[test]
+        fn invert_undoes_stage(mappings in mappings()) {
+            let stage = stage(mappings);
+            if let Ok(inverse) = stage.invert() {
+                for v in 0..200 {
+                    prop_assert_eq!(inverse.get_destination(stage.get_destination(v)), v);
+                }
+                prop_assert_eq!(inverse.invert(), Ok(stage));
+            }
+        }
+    }
+
+    #[test]
+    fn stage_normalise() {
+        let stage = stage(vec![Mapping::new(52, 50, 48), Mapping::new(50, 98, 2)]);
+        assert_eq!(
+            stage.to_string(),
+            "0..50 -> 0..50\n50..98 -> 52..100\n98..100 -> 50..52\n100..18446744073709551615 -> 100..18446744073709551615\n"
+        );
+        assert_eq!(
+            Stage::new(vec![Mapping::new(0, 10, 5), Mapping::new(50, 14, 3)]),
+            Err(StageError::Overlap(
+                Mapping::new(0, 10, 5),
+                Mapping::new(50, 14, 3)
+            ))
         );
+        assert_eq!(
+            Stage::new(vec![Mapping::new(0, u64::MAX - 5, 10)]),
+            Err(StageError::OutOfRange(Mapping::new(0, u64::MAX - 5, 10)))
+        );
+        let err = Stage::new(vec![Mapping::new(u64::MAX, 0, 10)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mapping of 10 values from 0 to 18446744073709551615 runs past 18446744073709551615"
+        );
+
+        // Mappings reaching the end of the values still map all the way up to it
+        let top = self::stage(vec![Mapping::new(u64::MAX - 10, 0, 10)]);
+        assert_eq!(top.get_destination(9), u64::MAX - 1);
+        assert_eq!(top.get_destination(u64::MAX - 1), u64::MAX - 1);
+        assert_eq!(
+            top.map_ranges(&[SeedRange::new(5, 10)]),
+            vec![SeedRange::new(u64::MAX - 5, 5), SeedRange::new(10, 5)]
+        );
+    }
+
+    #[test]
+    fn stage_invert() {
+        let stage = stage(vec![Mapping::new(52, 50, 48), Mapping::new(50, 98, 2)]);
+        let inverse = stage.invert().unwrap();
+        assert_eq!(inverse.get_destination(52), 50);
+        assert_eq!(inverse.get_destination(50), 98);
+        assert_eq!(stage.compose(&inverse), Stage::identity());
+
+        // 5 is sent to 10, but so is 10 itself
+        let err = self::stage(vec![Mapping::new(10, 5, 1)])
+            .invert()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mappings 6..18446744073709551615 -> 6..18446744073709551615 and 5..6 -> 10..11 share destinations so can't be inverted"
+        );
+    }
+
+    const TEST: &str = "
+        seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-fertilizer map:
+        0 15 37
+        37 52 2
+        39 0 15
+
+        fertilizer-to-water map:
+        49 53 8
+        0 11 42
+        42 0 7
+        57 7 4
+
+        water-to-light map:
+        88 18 7
+        18 25 70
+
+        light-to-temperature map:
+        45 77 23
+        81 45 19
+        68 64 13
+
+        temperature-to-humidity map:
+        0 69 1
+        1 0 69
+
+        humidity-to-location map:
+        60 56 37
+        56 93 4
+    ";
+
+    #[test]
+    fn both_test() {
+        let input = parse(TEST).unwrap();
         assert_eq!(part1(&input), 35);
         assert_eq!(part2(&input), 46);
     }
 
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(5));
-        assert_eq!(part1(&input), 462648396);
-        assert_eq!(part2(&input), 2520479);
+    fn queries() {
+        let almanac = parse(TEST).unwrap();
+        assert_eq!(almanac.map("seed", 79, "water"), Ok(81));
+        assert_eq!(almanac.map("soil", 81, "soil"), Ok(81));
+        assert_eq!(almanac.map("seed", 14, "location"), Ok(43));
+        assert_eq!(
+            almanac.sources("seed", "location", &[SeedRange::new(46, 1)]),
+            Ok(vec![SeedRange::new(82, 1)])
+        );
+        let seeds = almanac
+            .map_ranges("seed", &[SeedRange::new(79, 14)], "location")
+            .unwrap();
+        assert_eq!(seeds.iter().map(|r| r.range).sum::<u64>(), 14);
+    }
+
+    #[test]
+    fn compose_seed_to_location() {
+        let almanac = parse(TEST).unwrap();
+        let composed = almanac.compose("seed", "location").unwrap();
+        for seed in 0..200 {
+            assert_eq!(
+                Ok(composed.get_destination(seed)),
+                almanac.map("seed", seed, "location")
+            );
+        }
+        let printed = composed.to_string();
+        assert_eq!(printed.lines().count(), composed.mappings().len());
+        assert!(printed.starts_with("0..14 -> 22..36\n14..15 -> 43..44\n"));
+        // Seed 82 is the first seed from part 2's ranges to reach location 46
+        assert!(printed.contains("\n82..92 -> 46..56\n"));
+    }
+
+    #[test]
+    fn custom_chain() {
+        let almanac = parse(
+            "seeds: 1 2 3
+
+            seed-to-colour map:
+            10 0 5
+
+            colour-to-shape map:
+            20 10 2",
+        )
+        .unwrap();
+        assert_eq!(almanac.map("seed", 1, "shape"), Ok(21));
+        assert_eq!(almanac.map("seed", 3, "shape"), Ok(13));
+        assert_eq!(
+            almanac.map("seed", 1, "location"),
+            Err(ChainError::Broken {
+                from: "seed".to_string(),
+                to: "location".to_string(),
+                at: "shape".to_string(),
+            })
+        );
+        assert_eq!(
+            almanac.map("seed", 1, "location").unwrap_err().to_string(),
+            "no map from shape on the way from seed to location"
+        );
+    }
+
+    #[test]
+    fn cyclic_chain() {
+        let almanac = parse("seeds: 1\nseed-to-soil map:\nsoil-to-seed map:").unwrap();
+        assert_eq!(
+            almanac.map("seed", 1, "water"),
+            Err(ChainError::Cycle {
+                from: "seed".to_string(),
+                to: "water".to_string(),
+                at: "seed".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let err = parse("seeds: 79 14 55 1e3").unwrap_err();
+        assert_eq!(err, ParseError::new(5, 1, 17, "1e3", "expected a number"));
+        let err = parse("seeds 79 14").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(5, 1, 12, "", "expected ':' after seeds")
+        );
+        let err = parse("seeds: 79 14\n50 98 2").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(5, 2, 1, "50 98 2", "expected a map header before mappings")
+        );
+        let err = parse("seeds: 79 14\nseed-soil map:").unwrap_err();
+        assert_eq!(err.reason, "expected a map header of the form X-to-Y map:");
+        let err = parse("seeds: 79\nseed-to-soil map:\nseed-to-water map:").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(5, 3, 1, "seed", "category already has a map")
+        );
+        let err = parse("seeds: 79\nseed-to-soil map:\n50 98 2\n10 99 5").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(
+                5,
+                4,
+                1,
+                "10 99 5",
+                "mapping overlaps another in the same map"
+            )
+        );
+        let err = parse("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(
+                5,
+                5,
+                1,
+                "52 50",
+                "expected destination, source and range for mapping"
+            )
+        );
+        let err = parse("seeds: 79\nseed-to-soil map:\n18446744073709551615 0 10").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(
+                5,
+                3,
+                1,
+                "18446744073709551615 0 10",
+                "mapping runs past the largest value"
+            )
+        );
+        assert!(parse("seeds: 79\nseed-to-soil map:\n18446744073709551605 0 10").is_ok());
     }
 }
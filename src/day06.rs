@@ -1,106 +1,219 @@
-use crate::parser;
+use std::ops::RangeInclusive;
 
-struct Race {
-    time: u64,
-    record: u64,
+use crate::parser::{self, Line, ParseError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64,
 }
 
 impl Race {
+    /// Every charging time which beats the record, or `None` if it can't be beaten.
+    ///
+    /// Charging for `t` travels `t * (time - t)`, so the record is broken between the roots of
+    /// `t² - time * t + record = 0`, found exactly with integer square roots. Working in u128
+    /// means `time²` can't overflow.
+    pub fn winning_charges(&self) -> Option<RangeInclusive<u64>> {
+        let time = u128::from(self.time);
+        let discriminant = (time * time).checked_sub(4 * u128::from(self.record))?;
+        let root = discriminant.isqrt();
+
+        // Round the lower root (time - √discriminant) / 2 down. It's only a whole number when
+        // the discriminant is a perfect square, otherwise it lies just below (time - root) / 2
+        let lower = match root * root == discriminant {
+            true => (time - root) / 2,
+            false => (time - root - 1) / 2,
+        };
+
+        // Equalling the record isn't enough, so the first win is just past the root. The
+        // winning options are symmetrical, finishing as far from the end as they started.
+        let first = lower + 1;
+        let last = time.checked_sub(first)?;
+        (first <= last).then_some(first as u64..=last as u64)
+    }
+
+    pub fn ways_to_win(&self) -> u64 {
+        self.winning_charges()
+            .map_or(0, |charges| charges.end() - charges.start() + 1)
+    }
+
     // The winning options are symmetrical i.e. there will be the same number of
     // losses on either side of the distribution, so just find when the record is
     // first broken.
-    pub fn ways_to_win(&self) -> u64 {
-        let mut result = 0;
+    #[cfg(test)]
+    pub fn winning_charges_scan(&self) -> Option<RangeInclusive<u64>> {
         for time_charging in 1..self.time {
             let time_remaining = self.time - time_charging;
-            let distance = time_charging * time_remaining;
-            if distance > self.record {
-                result = self.time - (2 * time_charging) + 1;
-                break;
+            let distance = u128::from(time_charging) * u128::from(time_remaining);
+            if distance > u128::from(self.record) {
+                return Some(time_charging..=time_remaining);
             }
         }
-        result
+        None
     }
 }
 
-fn parse_input_part_1(index: usize, input: &[String]) -> Vec<u64> {
-    input
-        .get(index)
-        .expect("Failed to extract input line")
-        .split(':')
-        .collect::<Vec<&str>>()
-        .get(1)
-        .expect("Failed to extract values")
-        .split_whitespace()
-        .map(|v| v.parse::<u64>().expect("Failed to parse values"))
-        .collect::<Vec<u64>>()
+/// The races on the sheet, read both as separate columns and with the kerning between the
+/// numbers ignored as one long race. The long race is `None` if its numbers don't fit in a
+/// `u64`, which leaves the columns usable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Races {
+    pub columns: Vec<Race>,
+    pub kerned: Option<Race>,
 }
 
-fn parse_input_part_2(index: usize, input: &[String]) -> u64 {
-    input
-        .get(index)
-        .expect("Failed to extract input line")
-        .split(':')
-        .collect::<Vec<&str>>()
-        .get(1)
-        .expect("Failed to extract values")
+/// The numbers following `label:` on a line, along with all of them joined together if
+/// that fits in a `u64`.
+fn parse_values<'a>(line: &Line<'a>, label: &str) -> Result<(Vec<u64>, Option<u64>), ParseError> {
+    let values = line
+        .text
+        .strip_prefix(label)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .ok_or_else(|| line.error(line.text, "expected a line of the form Label: values"))?
         .split_whitespace()
-        .collect::<Vec<&str>>()
-        .concat()
-        .parse::<u64>()
-        .expect("Failed to parse values")
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        return Err(line.missing("expected a number"));
+    }
+    let columns = values
+        .iter()
+        .map(|v| line.parse::<u64>(v, "expected a number"))
+        .collect::<Result<Vec<u64>, ParseError>>()?;
+    Ok((columns, values.concat().parse::<u64>().ok()))
 }
 
 #[aoc_generator(day6)]
-pub fn parse(input: &str) -> Vec<String> {
-    parser::test_input(input)
-}
+pub fn parse(input: &str) -> Result<Races, ParseError> {
+    let mut lines = parser::lines(6, input);
+    let time = lines
+        .next()
+        .ok_or_else(|| ParseError::new(6, 1, 1, "", "expected a line of times"))?;
+    let distance = lines
+        .next()
+        .ok_or_else(|| time.missing("expected a line of distances"))?;
 
-#[aoc(day6, part1)]
-pub fn part1(input: &[String]) -> u64 {
-    let times = parse_input_part_1(0, input);
-    let records = parse_input_part_1(1, input);
-    let mut races = Vec::new();
-    for i in 0..times.len() {
-        races.push(Race {
-            time: *times.get(i).expect("Expected time data"),
-            record: *records.get(i).expect("Expected record data"),
-        });
+    let (times, kerned_time) = parse_values(&time, "Time")?;
+    let (records, kerned_record) = parse_values(&distance, "Distance")?;
+    if times.len() != records.len() {
+        return Err(distance.error(
+            distance.text,
+            "expected the same number of distances as times",
+        ));
     }
 
-    let mut part_1 = Vec::new();
-    for race in &races {
-        part_1.push(race.ways_to_win());
-    }
-    part_1.iter().product::<u64>()
+    Ok(Races {
+        columns: times
+            .into_iter()
+            .zip(records)
+            .map(|(time, record)| Race { time, record })
+            .collect(),
+        kerned: kerned_time
+            .zip(kerned_record)
+            .map(|(time, record)| Race { time, record }),
+    })
+}
+
+#[aoc(day6, part1)]
+pub fn part1(input: &Races) -> u64 {
+    input.columns.iter().map(Race::ways_to_win).product()
 }
 
 #[aoc(day6, part2)]
-pub fn part2(input: &[String]) -> u64 {
-    let time = parse_input_part_2(0, input);
-    let record = parse_input_part_2(1, input);
-    Race { time, record }.ways_to_win()
+pub fn part2(input: &Races) -> u64 {
+    input
+        .kerned
+        .as_ref()
+        .expect("Expected the joined numbers to fit in a u64")
+        .ways_to_win()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    #[test_case(7, 9, Some(2..=5) ; "example 1")]
+    #[test_case(30, 200, Some(11..=19) ; "example 3")]
+    #[test_case(71530, 940200, Some(14..=71516) ; "example part 2")]
+    #[test_case(6, 8, Some(3..=3) ; "perfect square with one win")]
+    #[test_case(6, 9, None ; "record at the peak")]
+    #[test_case(10, 16, Some(3..=7) ; "perfect square")]
+    #[test_case(1, 0, None ; "no time to charge")]
+    #[test_case(0, 0, None ; "no time")]
+    #[test_case(u64::MAX, 0, Some(1..=u64::MAX - 1) ; "largest time")]
+    #[test_case(u64::MAX, u64::MAX, Some(2..=u64::MAX - 2) ; "largest record")]
+    fn winning_charges(time: u64, record: u64, expected: Option<RangeInclusive<u64>>) {
+        assert_eq!(Race { time, record }.winning_charges(), expected);
+    }
+
+    proptest! {
+        #[test]
+        fn closed_form_matches_scan(time in 0..2000u64, fraction in 0.0..1.1f64) {
+            // Records from nothing to just beyond the best possible distance
+            let record = ((time * time / 4) as f64 * fraction) as u64;
+            let race = Race { time, record };
+            prop_assert_eq!(race.winning_charges(), race.winning_charges_scan());
+        }
+
+        #[test]
+        fn closed_form_at_the_peak(time in 2..2000u64) {
+            // Either side of the best possible distance, where the roots are closest
+            let best = (time / 2) * (time - time / 2);
+            for record in best.saturating_sub(2)..=best {
+                let race = Race { time, record };
+                prop_assert_eq!(race.winning_charges(), race.winning_charges_scan());
+            }
+        }
+    }
 
     #[test]
     fn both_test() {
         let input = parse(
             "Time:      7  15   30
             Distance:  9  40  200",
-        );
+        )
+        .unwrap();
         assert_eq!(part1(&input), 288);
         assert_eq!(part2(&input), 71503);
     }
 
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(6));
-        assert_eq!(part1(&input), 781200);
-        assert_eq!(part2(&input), 49240091);
+    fn races() {
+        let races = parse("Time: 7 15\nDistance: 9 40").unwrap();
+        assert_eq!(
+            races,
+            Races {
+                columns: vec![
+                    Race { time: 7, record: 9 },
+                    Race {
+                        time: 15,
+                        record: 40
+                    }
+                ],
+                kerned: Some(Race {
+                    time: 715,
+                    record: 940
+                }),
+            }
+        );
+
+        // Too long to join, but the columns are still races of their own
+        let races = parse("Time: 7 15\nDistance: 99999999999 99999999999").unwrap();
+        assert_eq!(races.kerned, None);
+        assert_eq!(part1(&races), 0);
+    }
+
+    #[test_case("", 1, 1, "", "expected a line of times" ; "empty")]
+    #[test_case("Time: 7 15\nDistance: 9", 2, 1, "Distance: 9", "expected the same number of distances as times" ; "mismatched columns")]
+    #[test_case("Time: 7 15", 1, 11, "", "expected a line of distances" ; "missing distances")]
+    #[test_case("Time: 7 1x\nDistance: 9 40", 1, 9, "1x", "expected a number" ; "number")]
+    #[test_case("Time 7 15\nDistance: 9 40", 1, 1, "Time 7 15", "expected a line of the form Label: values" ; "label")]
+    #[test_case("Time:\nDistance: 9 40", 1, 6, "", "expected a number" ; "no times")]
+    #[test_case("Time: 7 15\nDistance:", 2, 10, "", "expected a number" ; "no distances")]
+    fn parse_error(input: &str, line: usize, column: usize, token: &str, reason: &str) {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err, ParseError::new(6, line, column, token, reason));
     }
 }
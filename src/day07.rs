@@ -1,9 +1,12 @@
-use std::{cmp::Ordering, collections::HashSet};
+use std::{cmp::Ordering, collections::HashMap, fmt};
 
-use crate::parser;
+use crate::parser::{self, ParseError};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
+/// Every card in the deck, weakest first in the standard game
+const DECK: &str = "23456789TJQKA";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
@@ -13,87 +16,176 @@ enum HandType {
     FiveOfAKind,
 }
 
-#[derive(Eq, PartialEq)]
+impl HandType {
+    /// Categorise a hand from how many of each card it holds, largest count first.
+    pub fn from_counts(counts: &[usize]) -> Self {
+        match counts {
+            [n, ..] if *n >= 5 => HandType::FiveOfAKind,
+            [4, ..] => HandType::FourOfAKind,
+            [3, 2, ..] => HandType::FullHouse,
+            [3, ..] => HandType::ThreeOfAKind,
+            [2, 2, ..] => HandType::TwoPair,
+            [2, ..] => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
+}
+
+/// How hands with the same type are split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Compare the cards in the order they were dealt, the first difference deciding
+    CardOrder,
+    /// Compare the cards strongest first, as in poker
+    Strongest,
+}
+
+/// The rules of a game of Camel Cards.
+#[derive(Debug, Clone)]
+pub struct Ruleset {
+    /// Cards from weakest to strongest. Any card missing is weaker than all of them.
+    pub order: Vec<char>,
+    /// Cards which stand in for whichever card makes the best hand. They keep their own
+    /// place in `order` when breaking ties.
+    pub wildcards: Vec<char>,
+    pub tie_break: TieBreak,
+    /// Categorises a hand from the counts of each card, largest first, once wildcards have
+    /// joined the largest group
+    pub categorise: fn(&[usize]) -> HandType,
+}
+
+impl Ruleset {
+    /// Part 1: no wildcards and jacks ranked between tens and queens.
+    pub fn standard() -> Self {
+        Self {
+            order: DECK.chars().collect(),
+            wildcards: Vec::new(),
+            tie_break: TieBreak::CardOrder,
+            categorise: HandType::from_counts,
+        }
+    }
+
+    /// Part 2: jacks are jokers, wild and the weakest card.
+    pub fn jokers() -> Self {
+        Self {
+            order: "J23456789TQKA".chars().collect(),
+            wildcards: vec!['J'],
+            ..Self::standard()
+        }
+    }
+
+    /// Position of a card in the order, counting from 1 so that unknown cards are weakest.
+    pub fn strength(&self, card: char) -> usize {
+        self.order
+            .iter()
+            .position(|&c| c == card)
+            .map_or(0, |p| p + 1)
+    }
+
+    pub fn hand_type(&self, cards: &[char]) -> HandType {
+        self.classify(cards).0
+    }
+
+    /// The type of a hand, along with the card any wildcards in it were treated as.
+    pub fn classify(&self, cards: &[char]) -> (HandType, Option<char>) {
+        let mut histogram = HashMap::new();
+        let mut wild = 0;
+        for &card in cards {
+            if self.wildcards.contains(&card) {
+                wild += 1;
+            } else {
+                *histogram.entry(card).or_insert(0) += 1;
+            }
+        }
+
+        // Wildcards are always best joining the largest group, the stronger card on a tie
+        let target = histogram
+            .iter()
+            .max_by_key(|&(&card, &count)| (count, self.strength(card)))
+            .map(|(&card, _)| card)
+            // A hand of nothing but wildcards becomes five of the strongest card
+            .or_else(|| {
+                self.order
+                    .iter()
+                    .rev()
+                    .find(|c| !self.wildcards.contains(c))
+                    .copied()
+            });
+
+        let mut counts = histogram.into_values().collect::<Vec<usize>>();
+        counts.sort_by(|a, b| b.cmp(a));
+        match counts.first_mut() {
+            Some(largest) => *largest += wild,
+            None => counts.push(wild),
+        }
+        let wild_as = if wild > 0 { target } else { None };
+        ((self.categorise)(&counts), wild_as)
+    }
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            HandType::HighCard => "High card",
+            HandType::OnePair => "One pair",
+            HandType::TwoPair => "Two pair",
+            HandType::ThreeOfAKind => "Three of a kind",
+            HandType::FullHouse => "Full house",
+            HandType::FourOfAKind => "Four of a kind",
+            HandType::FiveOfAKind => "Five of a kind",
+        };
+        // Pad so that tables line up
+        f.pad(name)
+    }
+}
+
+/// A hand as dealt, before any rules are applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deal {
+    pub cards: Vec<char>,
+    pub bid: u32,
+}
+
+/// A hand valued under a ruleset. Hands order by type, then by the ruleset's tie-break.
+#[derive(Debug)]
 struct Hand {
-    cards: Vec<u32>,
+    cards: Vec<char>,
     hand_type: HandType,
+    wild_as: Option<char>,
+    tie_break: Vec<usize>,
     bid: u32,
 }
 
 impl Hand {
-    pub fn new(input: &str, jokers: bool) -> Self {
-        let split = input.split_whitespace().collect::<Vec<&str>>();
-        let cards = split
-            .first()
-            .expect("Expected cards input")
-            .chars()
-            .map(|c| match c {
-                'A' => 14,
-                'K' => 13,
-                'Q' => 12,
-                'J' => {
-                    if jokers {
-                        1
-                    } else {
-                        11
-                    }
-                }
-                'T' => 10,
-                _ => c.to_digit(10).expect("Unable to parse card number"),
-            })
-            .collect::<Vec<u32>>();
-        let bid = split
-            .last()
-            .expect("Unable to extract bid")
-            .parse::<u32>()
-            .expect("Unable to parse bid");
-
-        // Don't add jokers to the set
-        let set = cards.iter().filter(|c| **c != 1).collect::<HashSet<&u32>>();
-
-        let hand_type = match set.len() {
-            0 => {
-                if jokers {
-                    HandType::FiveOfAKind
-                } else {
-                    panic!("Invalid hand")
-                }
-            }
-            1 => HandType::FiveOfAKind,
-            2 => {
-                // Could either be a FullHouse (AAA KK) or FourOfAKind (AAAA k)
-                let mut t = HandType::FullHouse;
-                for card in set {
-                    if cards.iter().filter(|c| *c == card || **c == 1).count() == 4 {
-                        t = HandType::FourOfAKind;
-                        break;
-                    }
-                }
-                t
-            }
-            3 => {
-                // Could either be TwoPair (AA KK Q) or ThreeOfAKind (AAA K Q)
-                let mut t = HandType::TwoPair;
-                for card in set {
-                    if cards.iter().filter(|c| *c == card || **c == 1).count() == 3 {
-                        t = HandType::ThreeOfAKind;
-                        break;
-                    }
-                }
-                t
-            }
-            4 => HandType::OnePair,
-            5 => HandType::HighCard,
-            _ => panic!("Invalid hand"),
-        };
+    pub fn new(deal: &Deal, ruleset: &Ruleset) -> Self {
+        let mut tie_break = deal
+            .cards
+            .iter()
+            .map(|&c| ruleset.strength(c))
+            .collect::<Vec<usize>>();
+        if ruleset.tie_break == TieBreak::Strongest {
+            tie_break.sort_by(|a, b| b.cmp(a));
+        }
+
+        let (hand_type, wild_as) = ruleset.classify(&deal.cards);
         Hand {
-            cards,
+            cards: deal.cards.clone(),
             hand_type,
-            bid,
+            wild_as,
+            tie_break,
+            bid: deal.bid,
         }
     }
 }
 
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand {}
+
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Hand) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -105,8 +197,8 @@ impl Ord for Hand {
         match self.hand_type.cmp(&other.hand_type) {
             Ordering::Less => Ordering::Less,
             Ordering::Equal => {
-                for i in 0..5 {
-                    match self.cards[i].cmp(&other.cards[i]) {
+                for (a, b) in self.tie_break.iter().zip(&other.tie_break) {
+                    match a.cmp(b) {
                         Ordering::Less => return Ordering::Less,
                         Ordering::Greater => return Ordering::Greater,
                         Ordering::Equal => (),
@@ -119,63 +211,205 @@ impl Ord for Hand {
     }
 }
 
-#[aoc_generator(day7)]
-pub fn parse(input: &str) -> Vec<String> {
-    parser::test_input(input)
+/// Why a hand finished where it did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub cards: Vec<char>,
+    pub hand_type: HandType,
+    /// The card any wildcards were treated as, if the hand held any
+    pub wild_as: Option<char>,
+    /// Position from 1 for the weakest hand
+    pub rank: u32,
+    pub bid: u32,
+    pub winnings: u32,
 }
 
-#[aoc(day7, part1)]
-pub fn part1(input: &[String]) -> u32 {
-    let mut part_1_hands = input
+/// Every hand played under a ruleset, weakest first.
+pub fn explain(deals: &[Deal], ruleset: &Ruleset) -> Vec<Explanation> {
+    let mut hands = deals
         .iter()
-        .map(|line| Hand::new(line, false))
+        .map(|deal| Hand::new(deal, ruleset))
         .collect::<Vec<Hand>>();
 
-    part_1_hands.sort();
+    hands.sort();
 
-    part_1_hands
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (i, hand)| acc + hand.bid * (i as u32 + 1))
+    hands
+        .into_iter()
+        .zip(1..)
+        .map(|(hand, rank)| Explanation {
+            cards: hand.cards,
+            hand_type: hand.hand_type,
+            wild_as: hand.wild_as,
+            rank,
+            bid: hand.bid,
+            winnings: hand.bid * rank,
+        })
+        .collect()
 }
 
-#[aoc(day7, part2)]
-pub fn part2(input: &[String]) -> u32 {
-    let mut part_2_hands = input
-        .iter()
-        .map(|line| Hand::new(line, true))
-        .collect::<Vec<Hand>>();
+/// Total winnings of every hand played under a ruleset.
+pub fn winnings(deals: &[Deal], ruleset: &Ruleset) -> u32 {
+    explain(deals, ruleset).iter().map(|e| e.winnings).sum()
+}
 
-    part_2_hands.sort();
+/// Lay out explained hands as a table, one row per hand followed by the total.
+pub fn table(explanations: &[Explanation]) -> String {
+    let mut table = format!(
+        "{:>5}  {:<5}  {:<15}  {:<4}  {:>5}  {:>9}\n",
+        "Rank", "Cards", "Type", "Wild", "Bid", "Winnings"
+    );
+    for e in explanations {
+        table += &format!(
+            "{:>5}  {:<5}  {:<15}  {:<4}  {:>5}  {:>9}\n",
+            e.rank,
+            e.cards.iter().collect::<String>(),
+            e.hand_type,
+            e.wild_as.map_or("-".to_string(), String::from),
+            e.bid,
+            e.winnings
+        );
+    }
+    let total = explanations.iter().map(|e| e.winnings).sum::<u32>();
+    table += &format!("Total winnings: {total}\n");
+    table
+}
 
-    part_2_hands
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (i, hand)| acc + hand.bid * (i as u32 + 1))
+#[aoc_generator(day7)]
+pub fn parse(input: &str) -> Result<Vec<Deal>, ParseError> {
+    parser::lines(7, input)
+        .map(|line| {
+            let mut split = line.text.split_whitespace();
+            let cards = split.next().ok_or_else(|| line.missing("expected cards"))?;
+            if let Some((i, c)) = cards.char_indices().find(|&(_, c)| !DECK.contains(c)) {
+                return Err(line.error(&cards[i..i + c.len_utf8()], "unknown card"));
+            }
+            if cards.len() != 5 {
+                return Err(line.error(cards, "expected five cards"));
+            }
+            let bid = split.next().ok_or_else(|| line.missing("expected a bid"))?;
+            let bid = line.parse::<u32>(bid, "unable to parse bid")?;
+            Ok(Deal {
+                cards: cards.chars().collect(),
+                bid,
+            })
+        })
+        .collect()
+}
+
+#[aoc(day7, part1)]
+pub fn part1(input: &[Deal]) -> u32 {
+    winnings(input, &Ruleset::standard())
+}
+
+#[aoc(day7, part2)]
+pub fn part2(input: &[Deal]) -> u32 {
+    winnings(input, &Ruleset::jokers())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use test_case::test_case;
+
+    const TEST: &str = "
+        32T3K 765
+        T55J5 684
+        KK677 28
+        KTJJT 220
+        QQQJA 483
+    ";
 
     #[test]
     fn both_test() {
-        let input = parse(
-            "32T3K 765
-            T55J5 684
-            KK677 28
-            KTJJT 220
-            QQQJA 483",
-        );
+        let input = parse(TEST).unwrap();
         assert_eq!(part1(&input), 6440);
         assert_eq!(part2(&input), 5905);
     }
 
+    #[test_case("32T3K", HandType::OnePair, HandType::OnePair ; "pair")]
+    #[test_case("T55J5", HandType::ThreeOfAKind, HandType::FourOfAKind ; "joker joins triple")]
+    #[test_case("KTJJT", HandType::TwoPair, HandType::FourOfAKind ; "jokers join pair")]
+    #[test_case("2345J", HandType::HighCard, HandType::OnePair ; "joker makes pair")]
+    #[test_case("JJJJJ", HandType::FiveOfAKind, HandType::FiveOfAKind ; "all jokers")]
+    #[test_case("JJ2J3", HandType::ThreeOfAKind, HandType::FourOfAKind ; "mostly jokers")]
+    #[test_case("22JJ3", HandType::TwoPair, HandType::FourOfAKind ; "two pair with jokers")]
+    #[test_case("2233J", HandType::TwoPair, HandType::FullHouse ; "full house with joker")]
+    fn hand_types(cards: &str, standard: HandType, jokers: HandType) {
+        let cards = cards.chars().collect::<Vec<char>>();
+        assert_eq!(Ruleset::standard().hand_type(&cards), standard);
+        assert_eq!(Ruleset::jokers().hand_type(&cards), jokers);
+    }
+
+    /// Winnings of the bids in rank order, weakest first.
+    fn ranked(bids: &[u32]) -> u32 {
+        bids.iter().zip(1..).map(|(bid, rank)| bid * rank).sum()
+    }
+
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(7));
-        assert_eq!(part1(&input), 247815719);
-        assert_eq!(part2(&input), 248747492);
+    fn custom_rulesets() {
+        let deals = parse("2345A 1\nQQ234 2\n22TKA 3\nT9876 4").unwrap();
+        assert_eq!(
+            winnings(&deals, &Ruleset::standard()),
+            ranked(&[1, 4, 3, 2])
+        );
+
+        // Deuces and jacks wild: twos are still the weakest card when breaking ties
+        let deuces = Ruleset {
+            wildcards: vec!['2', 'J'],
+            ..Ruleset::standard()
+        };
+        let cards = "2J2J7".chars().collect::<Vec<char>>();
+        assert_eq!(deuces.hand_type(&cards), HandType::FiveOfAKind);
+        assert_eq!(winnings(&deals, &deuces), ranked(&[4, 1, 3, 2]));
+
+        // Poker style tie-break, where the highest card decides rather than the first
+        let poker = Ruleset {
+            tie_break: TieBreak::Strongest,
+            ..Ruleset::standard()
+        };
+        assert_eq!(winnings(&deals, &poker), ranked(&[4, 1, 2, 3]));
+    }
+
+    #[test]
+    fn explain_example() {
+        let deals = parse(TEST).unwrap();
+        let explanations = explain(&deals, &Ruleset::jokers());
+        let qqqja = &explanations[3];
+        assert_eq!(qqqja.cards, vec!['Q', 'Q', 'Q', 'J', 'A']);
+        assert_eq!(qqqja.hand_type, HandType::FourOfAKind);
+        assert_eq!(qqqja.wild_as, Some('Q'));
+        assert_eq!((qqqja.rank, qqqja.bid, qqqja.winnings), (4, 483, 1932));
+
+        assert_eq!(
+            table(&explanations),
+            " Rank  Cards  Type             Wild    Bid   Winnings
+    1  32T3K  One pair         -       765        765
+    2  KK677  Two pair         -        28         56
+    3  T55J5  Four of a kind   5       684       2052
+    4  QQQJA  Four of a kind   Q       483       1932
+    5  KTJJT  Four of a kind   T       220       1100
+Total winnings: 5905
+"
+        );
+    }
+
+    #[test_case("2345J", Some('5') ; "strongest single")]
+    #[test_case("KKQQJ", Some('K') ; "stronger pair")]
+    #[test_case("2223J", Some('2') ; "largest group")]
+    #[test_case("JJJJJ", Some('A') ; "all jokers")]
+    #[test_case("23456", None ; "no jokers")]
+    fn jokers_treated_as(cards: &str, expected: Option<char>) {
+        let cards = cards.chars().collect::<Vec<char>>();
+        assert_eq!(Ruleset::jokers().classify(&cards).1, expected);
+    }
+
+    #[test_case("32T3K 765\n\nT55J5 684", 2, 1, "", "expected cards" ; "blank line")]
+    #[test_case("32T3K 765\nT55X5 684", 2, 4, "X", "unknown card" ; "unknown card")]
+    #[test_case("32T3K 765\nT55J 684", 2, 1, "T55J", "expected five cards" ; "short hand")]
+    #[test_case("32T3K 765\nT55J5", 2, 6, "", "expected a bid" ; "missing bid")]
+    #[test_case("32T3K 765\nT55J5 6a4", 2, 7, "6a4", "unable to parse bid" ; "bid")]
+    fn parse_error(input: &str, line: usize, column: usize, token: &str, reason: &str) {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err, ParseError::new(7, line, column, token, reason));
     }
 }
@@ -1,136 +1,418 @@
-use num_integer::lcm;
+use num_integer::{ExtendedGcd, Integer};
 use std::collections::HashMap;
 
-use crate::parser;
+use crate::parser::{self, ParseError};
 
-#[derive(Eq, Hash, PartialEq)]
-enum NodeType {
-    Starting,
-    Normal,
-    Terminating,
+/// The map of the desert, with nodes numbered in the order they are listed so that each
+/// step is a couple of array lookups.
+#[derive(Debug)]
+pub struct Network {
+    /// 0 to go left, 1 to go right
+    instructions: Vec<u8>,
+    names: Vec<String>,
+    /// The nodes reached from each node by going left and right
+    next: Vec<[usize; 2]>,
 }
 
-enum Part {
-    Part1,
-    Part2,
+/// The walk of one ghost from its start. The state of a ghost is its node together with
+/// its position in the instructions, so once a state repeats the ghost is in a loop which
+/// it follows forever.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Steps taken before the ghost first enters the loop
+    pub offset: usize,
+    /// Steps taken to go once round the loop
+    pub length: usize,
+    /// Steps before the loop at which the ghost is on a terminating node
+    pub lead_in: Vec<usize>,
+    /// Steps during the first time round the loop at which the ghost is on a terminating node,
+    /// each within `offset..offset + length`
+    pub ends: Vec<usize>,
 }
 
-struct Node {
-    id: String,
-    left: String,
-    right: String,
-    node_type: NodeType,
+impl Cycle {
+    /// Whether the ghost is on a terminating node after `steps` steps.
+    pub fn ends_at(&self, steps: usize) -> bool {
+        if steps < self.offset {
+            self.lead_in.contains(&steps)
+        } else {
+            let position = self.offset + (steps - self.offset) % self.length;
+            self.ends.contains(&position)
+        }
+    }
 }
 
-impl Node {
-    pub fn new(input: &str, part: &Part) -> Self {
-        let split = input.split_whitespace().collect::<Vec<&str>>();
-        let id = split
-            .first()
-            .expect("Expected a node id at the start of the input");
-        let left = split
-            .get(2)
-            .expect("Expected a left instruction")
-            .trim_matches(['(', ',']);
-        let right = split
-            .get(3)
-            .expect("Expected a right instruction")
-            .trim_matches(')');
-        let node_type = match part {
-            Part::Part1 => match *id {
-                "AAA" => NodeType::Starting,
-                "ZZZ" => NodeType::Terminating,
-                _ => NodeType::Normal,
-            },
-            Part::Part2 => match id
-                .chars()
-                .nth(2)
-                .expect("Expected id to have a 3rd character")
-            {
-                'A' => NodeType::Starting,
-                'Z' => NodeType::Terminating,
-                _ => NodeType::Normal,
-            },
+impl Network {
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The walk of a ghost starting at each node matching `start`, in the order the nodes
+    /// are listed, where nodes matching `end` are terminating.
+    pub fn cycles(&self, start: impl Fn(&str) -> bool, end: impl Fn(&str) -> bool) -> Vec<Cycle> {
+        let terminating = self.names.iter().map(|n| end(n)).collect::<Vec<bool>>();
+        (0..self.names.len())
+            .filter(|&node| start(&self.names[node]))
+            .map(|node| self.cycle(node, &terminating))
+            .collect()
+    }
+
+    /// The fewest steps after which every ghost is on a terminating node, or `None` if they
+    /// never are all at once.
+    pub fn steps(&self, start: impl Fn(&str) -> bool, end: impl Fn(&str) -> bool) -> Option<usize> {
+        // Ghosts can reach terminating nodes many times, before or during their loop, so
+        // rather than assuming each loop starts at its first end find when they all line up
+        earliest_alignment(&self.cycles(start, end))
+    }
+
+    fn cycle(&self, start: usize, terminating: &[bool]) -> Cycle {
+        let len = self.instructions.len();
+        // A state only repeats once a node starting a pass of the instructions repeats, so
+        // only those need remembering. `seen` holds the pass each node last started.
+        let mut seen = vec![usize::MAX; self.names.len()];
+        let mut path = Vec::new();
+        let mut node = start;
+        let length = loop {
+            if path.len() % len == 0 {
+                let pass = path.len() / len;
+                if seen[node] != usize::MAX {
+                    break (pass - seen[node]) * len;
+                }
+                seen[node] = pass;
+            }
+            path.push(node);
+            node = self.next[node][self.instructions[(path.len() - 1) % len] as usize];
         };
+        path.push(node);
 
-        Node {
-            id: id.to_string(),
-            left: left.to_string(),
-            right: right.to_string(),
-            node_type,
+        // The loop may have begun part way through the pass, at the first step which the
+        // ghost is back at a whole loop later
+        let offset = (0..)
+            .find(|&step| path[step] == path[step + length])
+            .expect("Path repeats by the time a pass does");
+        let (lead_in, ends) = (0..offset + length)
+            .filter(|&step| terminating[path[step]])
+            .partition(|&step| step < offset);
+        Cycle {
+            offset,
+            length,
+            lead_in,
+            ends,
         }
     }
 }
 
-#[aoc_generator(day8)]
-pub fn parse(input: &str) -> Vec<String> {
-    parser::test_input(input)
+/// Combine `x ≡ a (mod m)` and `x ≡ b (mod n)` into a single congruence, using the Chinese
+/// Remainder Theorem generalised to moduli which need not be coprime. `None` when no number
+/// satisfies both.
+fn crt((a, m): (i128, i128), (b, n): (i128, i128)) -> Option<(i128, i128)> {
+    let ExtendedGcd { gcd, x, .. } = m.extended_gcd(&n);
+    if (b - a) % gcd != 0 {
+        return None;
+    }
+    // m * x ≡ gcd (mod n), so stepping from `a` by multiples of m reaches b
+    let modulus = m.checked_mul(n / gcd)?;
+    let k = ((b - a) / gcd).checked_mul(x)?.rem_euclid(n / gcd);
+    Some((
+        a.checked_add(m.checked_mul(k)?)?.rem_euclid(modulus),
+        modulus,
+    ))
 }
 
-#[aoc(day8, part1)]
-fn part1(input: &Vec<String>) -> usize {
-    let (nodes, instructions) = parse_input(input, Part::Part1);
-    solve(&nodes, &instructions)
-}
+/// The fewest steps after which every ghost is on a terminating node at the same time.
+pub fn earliest_alignment(cycles: &[Cycle]) -> Option<usize> {
+    let settled = cycles.iter().map(|c| c.offset).max()?;
 
-#[aoc(day8, part2)]
-fn part2(input: &Vec<String>) -> usize {
-    let (nodes, instructions) = parse_input(input, Part::Part2);
-    solve(&nodes, &instructions)
-}
+    // Before every ghost is in its loop, some ghost must still be in its lead-in
+    let early = cycles
+        .iter()
+        .max_by_key(|c| c.offset)?
+        .lead_in
+        .iter()
+        .copied()
+        .find(|&steps| cycles.iter().all(|c| c.ends_at(steps)));
+    if early.is_some() {
+        return early;
+    }
 
-fn parse_input(input: &Vec<String>, part: Part) -> (HashMap<String, Node>, String) {
-    let mut instructions = String::new();
-    let mut nodes = HashMap::new();
-    for line in input {
-        if instructions.is_empty() {
-            instructions = line.to_string();
-            continue;
-        }
-        if line.is_empty() {
-            continue;
-        }
-        let node = Node::new(line, &part);
-        nodes.insert(node.id.clone(), node);
+    // After that, each ghost ends on some residue of its loop length. Combine every choice of
+    // residue to find the classes of steps at which they all end.
+    let mut classes = vec![(0i128, 1i128)];
+    for c in cycles {
+        let length = c.length as i128;
+        classes = classes
+            .iter()
+            .flat_map(|&class| {
+                c.ends
+                    .iter()
+                    .filter_map(move |&end| crt(class, (end as i128 % length, length)))
+            })
+            .collect();
+        classes.sort_unstable();
+        classes.dedup();
     }
-    (nodes, instructions)
+
+    let settled = settled as i128;
+    classes
+        .into_iter()
+        .filter_map(|(residue, modulus)| {
+            // Smallest step in the class at which every ghost is in its loop
+            let below = (residue - settled).rem_euclid(modulus);
+            usize::try_from(settled + below).ok()
+        })
+        .min()
 }
 
-fn solve(nodes: &HashMap<String, Node>, instructions: &str) -> usize {
-    let starting_nodes = nodes
-        .values()
-        .filter(|n| n.node_type == NodeType::Starting)
-        .collect::<Vec<&Node>>();
-
-    // Consider the number of steps in each path separately
-    // then find the lowest common multiple.
-    let mut path_steps = Vec::new();
-    for starting_node in starting_nodes {
-        let mut steps = 0;
-        let mut node = starting_node;
-        while node.node_type != NodeType::Terminating {
-            let instruction = instructions
-                .chars()
-                .nth(steps % instructions.len())
-                .expect("Expected instruction");
-            node = match instruction {
-                'L' => nodes.get(&node.left).expect("Expected to find a node"),
-                _ => nodes.get(&node.right).expect("Expected to find a node"),
-            };
-            steps += 1;
+#[aoc_generator(day8)]
+pub fn parse(input: &str) -> Result<Network, ParseError> {
+    let mut lines = parser::lines(8, input).filter(|line| !line.text.is_empty());
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::new(8, 1, 1, "", "expected instructions"))?;
+    let instructions = line
+        .text
+        .char_indices()
+        .map(|(i, c)| match c {
+            'L' => Ok(0),
+            'R' => Ok(1),
+            _ => Err(line.error_at(i, "expected L or R")),
+        })
+        .collect::<Result<Vec<u8>, ParseError>>()?;
+
+    let mut nodes = Vec::new();
+    for line in lines {
+        let (name, left, right) = line
+            .text
+            .split_once(" = (")
+            .and_then(|(name, rest)| {
+                let (left, right) = rest.strip_suffix(')')?.split_once(", ")?;
+                Some((name, left, right))
+            })
+            .ok_or_else(|| line.error(line.text, "expected a node of the form AAA = (BBB, CCC)"))?;
+        nodes.push((line, name, left, right));
+    }
+
+    let mut indices = HashMap::new();
+    for (index, (line, name, _, _)) in nodes.iter().enumerate() {
+        if indices.insert(*name, index).is_some() {
+            return Err(line.error(name, "duplicate node"));
         }
-        path_steps.push(steps);
     }
+    let next = nodes
+        .iter()
+        .map(|(line, _, left, right)| {
+            let find = |name| {
+                indices
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| line.error(name, "unknown node"))
+            };
+            Ok([find(left)?, find(right)?])
+        })
+        .collect::<Result<Vec<[usize; 2]>, ParseError>>()?;
+
+    Ok(Network {
+        instructions,
+        names: nodes
+            .iter()
+            .map(|(_, name, _, _)| name.to_string())
+            .collect(),
+        next,
+    })
+}
+
+#[aoc(day8, part1)]
+fn part1(network: &Network) -> usize {
+    network
+        .steps(|n| n == "AAA", |n| n == "ZZZ")
+        .expect("Never reached ZZZ")
+}
 
-    path_steps.iter().fold(1, |a, b| lcm(a, *b))
+#[aoc(day8, part2)]
+fn part2(network: &Network) -> usize {
+    network
+        .steps(|n| n.ends_with('A'), |n| n.ends_with('Z'))
+        .expect("Ghosts never all reach the end together")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use oracle::{Node, NodeType, Part};
+    use std::time::Instant;
     use test_case::test_case;
 
+    /// The network keyed by node names, as it was before being compiled, kept to check the
+    /// compiled network against. `cycle` finds each loop independently, by remembering every
+    /// (node, instruction) state, while `pass_cycle` runs the compiled network's search over
+    /// strings so the benchmark compares like with like.
+    mod oracle {
+        use super::super::{earliest_alignment, Cycle};
+        use std::collections::HashMap;
+
+        #[derive(Eq, Hash, PartialEq)]
+        pub enum NodeType {
+            Starting,
+            Normal,
+            Terminating,
+        }
+
+        pub enum Part {
+            Part1,
+            Part2,
+        }
+
+        pub struct Node {
+            pub id: String,
+            left: String,
+            right: String,
+            pub node_type: NodeType,
+        }
+
+        impl Node {
+            pub fn new(input: &str, part: &Part) -> Self {
+                let split = input.split_whitespace().collect::<Vec<&str>>();
+                let id = split
+                    .first()
+                    .expect("Expected a node id at the start of the input");
+                let left = split
+                    .get(2)
+                    .expect("Expected a left instruction")
+                    .trim_matches(['(', ',']);
+                let right = split
+                    .get(3)
+                    .expect("Expected a right instruction")
+                    .trim_matches(')');
+                let node_type = match part {
+                    Part::Part1 => match *id {
+                        "AAA" => NodeType::Starting,
+                        "ZZZ" => NodeType::Terminating,
+                        _ => NodeType::Normal,
+                    },
+                    Part::Part2 => match id
+                        .chars()
+                        .nth(2)
+                        .expect("Expected id to have a 3rd character")
+                    {
+                        'A' => NodeType::Starting,
+                        'Z' => NodeType::Terminating,
+                        _ => NodeType::Normal,
+                    },
+                };
+
+                Node {
+                    id: id.to_string(),
+                    left: left.to_string(),
+                    right: right.to_string(),
+                    node_type,
+                }
+            }
+        }
+
+        pub fn parse_input(input: &[String], part: Part) -> (HashMap<String, Node>, String) {
+            let mut instructions = String::new();
+            let mut nodes = HashMap::new();
+            for line in input {
+                if instructions.is_empty() {
+                    instructions = line.to_string();
+                    continue;
+                }
+                if line.is_empty() {
+                    continue;
+                }
+                let node = Node::new(line, &part);
+                nodes.insert(node.id.clone(), node);
+            }
+            (nodes, instructions)
+        }
+
+        pub fn cycle(nodes: &HashMap<String, Node>, instructions: &str, start: &Node) -> Cycle {
+            let instructions = instructions.as_bytes();
+            let mut seen = HashMap::new();
+            let mut terminating = Vec::new();
+            let mut node = start;
+            let mut steps = 0;
+            let offset = loop {
+                let index = steps % instructions.len();
+                if let Some(&first) = seen.get(&(node.id.as_str(), index)) {
+                    break first;
+                }
+                seen.insert((node.id.as_str(), index), steps);
+                if node.node_type == NodeType::Terminating {
+                    terminating.push(steps);
+                }
+                node = match instructions[index] {
+                    b'L' => nodes.get(&node.left).expect("Expected to find a node"),
+                    _ => nodes.get(&node.right).expect("Expected to find a node"),
+                };
+                steps += 1;
+            };
+
+            let ends = terminating.split_off(terminating.partition_point(|&s| s < offset));
+            Cycle {
+                offset,
+                length: steps - offset,
+                lead_in: terminating,
+                ends,
+            }
+        }
+
+        /// Only remember the node starting each pass of the instructions, then find where in
+        /// the pass the loop began, as `Network::cycle` does.
+        pub fn pass_cycle(
+            nodes: &HashMap<String, Node>,
+            instructions: &str,
+            start: &Node,
+        ) -> Cycle {
+            let instructions = instructions.as_bytes();
+            let len = instructions.len();
+            let mut seen = HashMap::new();
+            let mut path = Vec::new();
+            let mut node = start;
+            let length = loop {
+                if path.len() % len == 0 {
+                    let pass = path.len() / len;
+                    if let Some(first) = seen.insert(node.id.as_str(), pass) {
+                        break (pass - first) * len;
+                    }
+                }
+                path.push(node);
+                node = match instructions[(path.len() - 1) % len] {
+                    b'L' => nodes.get(&node.left).expect("Expected to find a node"),
+                    _ => nodes.get(&node.right).expect("Expected to find a node"),
+                };
+            };
+            path.push(node);
+
+            let offset = (0..)
+                .find(|&step| path[step].id == path[step + length].id)
+                .expect("Path repeats by the time a pass does");
+            let (lead_in, ends) = (0..offset + length)
+                .filter(|&step| path[step].node_type == NodeType::Terminating)
+                .partition(|&step| step < offset);
+            Cycle {
+                offset,
+                length,
+                lead_in,
+                ends,
+            }
+        }
+
+        pub type FindCycle = fn(&HashMap<String, Node>, &str, &Node) -> Cycle;
+
+        pub fn solve(nodes: &HashMap<String, Node>, instructions: &str, cycle: FindCycle) -> usize {
+            // Ghosts can reach terminating nodes many times, before or during their loop, so rather
+            // than assuming each loop starts at its first end find when all of them line up
+            let cycles = nodes
+                .values()
+                .filter(|n| n.node_type == NodeType::Starting)
+                .map(|start| cycle(nodes, instructions, start))
+                .collect::<Vec<Cycle>>();
+
+            earliest_alignment(&cycles).expect("Ghosts never all reach the end together")
+        }
+    }
+
     #[test_case(
         "
         RL
@@ -158,7 +440,9 @@ mod tests {
         ;"e2"
     )]
     fn part1_test(input: &str, answer: usize) {
-        assert_eq!(part1(&parse(input)), answer);
+        assert_eq!(part1(&parse(input).unwrap()), answer);
+        let (nodes, instructions) = oracle::parse_input(&parser::test_input(input), Part::Part1);
+        assert_eq!(oracle::solve(&nodes, &instructions, oracle::cycle), answer);
     }
 
     #[test]
@@ -174,14 +458,197 @@ mod tests {
             22C = (22Z, 22Z)
             22Z = (22B, 22B)
             XXX = (XXX, XXX)",
-        );
+        )
+        .unwrap();
         assert_eq!(part2(&input), 6);
     }
 
+    /// Steps each ghost takes to first reach a terminating node, assuming its loop begins there.
+    fn first_ends_lcm(cycles: &[Cycle]) -> usize {
+        cycles
+            .iter()
+            .map(|c| c.lead_in.first().or(c.ends.first()).copied().unwrap())
+            .fold(1, num_integer::lcm)
+    }
+
+    /// Cycles of the part 2 ghosts, checked against the oracle.
+    fn cycles(input: &str) -> Vec<Cycle> {
+        let cycles = parse(input)
+            .unwrap()
+            .cycles(|n| n.ends_with('A'), |n| n.ends_with('Z'));
+
+        let (nodes, instructions) = oracle::parse_input(&parser::test_input(input), Part::Part2);
+        let mut starts = nodes
+            .values()
+            .filter(|n| n.node_type == NodeType::Starting)
+            .collect::<Vec<&Node>>();
+        starts.sort_by_key(|n| &n.id);
+        let expected = starts
+            .iter()
+            .map(|start| oracle::cycle(&nodes, &instructions, start))
+            .collect::<Vec<Cycle>>();
+        assert_eq!(cycles, expected);
+        let by_pass = starts
+            .iter()
+            .map(|start| oracle::pass_cycle(&nodes, &instructions, start))
+            .collect::<Vec<Cycle>>();
+        assert_eq!(by_pass, expected);
+
+        cycles
+    }
+
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(8));
-        assert_eq!(part1(&input), 13771);
-        assert_eq!(part2(&input), 13129439557681);
+    fn cycle_of_example() {
+        let cycles = cycles(
+            "LR
+
+            11A = (11B, XXX)
+            11B = (XXX, 11Z)
+            11Z = (11B, XXX)
+            22A = (22B, XXX)
+            22B = (22C, 22C)
+            22C = (22Z, 22Z)
+            22Z = (22B, 22B)
+            XXX = (XXX, XXX)",
+        );
+        assert_eq!(
+            cycles[1],
+            Cycle {
+                offset: 1,
+                length: 6,
+                lead_in: vec![],
+                ends: vec![3, 6]
+            }
+        );
+        assert_eq!(earliest_alignment(&cycles), Some(6));
+        assert_eq!(first_ends_lcm(&cycles), 6);
+    }
+
+    #[test_case(
+        "
+        L
+
+        11A = (11B, 11B)
+        11B = (11Z, 11Z)
+        11Z = (11C, 11C)
+        11C = (11B, 11B)
+        22A = (22Z, 22Z)
+        22Z = (22B, 22B)
+        22B = (22Z, 22Z)
+        ",
+        Some(5),
+        2
+        ;"loop starts after first end"
+    )]
+    #[test_case(
+        "
+        L
+
+        33A = (33Z, 33Z)
+        33Z = (34Z, 34Z)
+        34Z = (33B, 33B)
+        33B = (33C, 33C)
+        33C = (33Z, 33Z)
+        44A = (44B, 44B)
+        44B = (44C, 44C)
+        44C = (44Z, 44Z)
+        44Z = (44B, 44B)
+        ",
+        Some(6),
+        3
+        ;"several ends in a loop"
+    )]
+    #[test_case(
+        "
+        L
+
+        55A = (55Z, 55Z)
+        55Z = (55B, 55B)
+        55B = (56Z, 56Z)
+        56Z = (56Z, 56Z)
+        66A = (66B, 66B)
+        66B = (66Z, 66Z)
+        66Z = (66B, 66B)
+        ",
+        Some(4),
+        2
+        ;"end before the loop"
+    )]
+    #[test_case(
+        "
+        L
+
+        77A = (77B, 77B)
+        77B = (77Z, 77Z)
+        77Z = (77B, 77B)
+        88A = (88Z, 88Z)
+        88Z = (88B, 88B)
+        88B = (88Z, 88Z)
+        ",
+        None,
+        2
+        ;"never aligned"
+    )]
+    fn lcm_shortcut_wrong(input: &str, expected: Option<usize>, lcm: usize) {
+        let cycles = cycles(input);
+        assert_eq!(earliest_alignment(&cycles), expected);
+        assert_eq!(first_ends_lcm(&cycles), lcm);
+    }
+
+    #[test_case((2, 4), (4, 6), Some((10, 12)) ; "shared factor")]
+    #[test_case((1, 4), (2, 6), None ; "incompatible")]
+    #[test_case((2, 3), (3, 5), Some((8, 15)) ; "coprime")]
+    #[test_case((0, 1), (4, 7), Some((4, 7)) ; "anything")]
+    fn generalised_crt(a: (i128, i128), b: (i128, i128), expected: Option<(i128, i128)>) {
+        assert_eq!(crt(a, b), expected);
+    }
+
+    #[test]
+    #[ignore = "timing comparison, run with --release --ignored --nocapture"]
+    fn benchmark() {
+        for account in crate::answers::accounts() {
+            let input = account.source.read(8).expect("Unable to read input");
+
+            let timer = Instant::now();
+            let network = parse(&input).unwrap();
+            let steps = part2(&network);
+            let compiled = timer.elapsed();
+
+            // Same search over strings, so the difference is down to compiling the network
+            let timer = Instant::now();
+            let (nodes, instructions) =
+                oracle::parse_input(&parser::test_input(&input), Part::Part2);
+            let by_pass = oracle::solve(&nodes, &instructions, oracle::pass_cycle);
+            let strings = timer.elapsed();
+
+            // Remembering every state as well
+            let timer = Instant::now();
+            let (nodes, instructions) =
+                oracle::parse_input(&parser::test_input(&input), Part::Part2);
+            let by_state = oracle::solve(&nodes, &instructions, oracle::cycle);
+            let states = timer.elapsed();
+
+            println!(
+                "{}: compiled {:?}, strings {:?} ({:.1}x), strings remembering every state {:?} ({:.1}x)",
+                account.name,
+                compiled,
+                strings,
+                strings.as_secs_f64() / compiled.as_secs_f64(),
+                states,
+                states.as_secs_f64() / compiled.as_secs_f64()
+            );
+            assert_eq!(by_pass, steps);
+            assert_eq!(by_state, steps);
+        }
+    }
+
+    #[test_case("\n\n", 1, 1, "", "expected instructions" ; "empty")]
+    #[test_case("LRX\n\nAAA = (AAA, AAA)", 1, 3, "X", "expected L or R" ; "instruction")]
+    #[test_case("LR\n\nAAA = (AAA AAA)", 3, 1, "AAA = (AAA AAA)", "expected a node of the form AAA = (BBB, CCC)" ; "node")]
+    #[test_case("LR\n\nAAA = (AAA, ZZZ)", 3, 13, "ZZZ", "unknown node" ; "unknown")]
+    #[test_case("LR\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)", 4, 1, "AAA", "duplicate node" ; "duplicate")]
+    fn parse_error(input: &str, line: usize, column: usize, token: &str, reason: &str) {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err, ParseError::new(8, line, column, token, reason));
     }
 }
@@ -1,46 +1,149 @@
+use std::{error::Error, fmt};
+
+use crate::parser::{self, ParseError};
+
+/// A calculation which doesn't fit in the integers used for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflowed")
+    }
+}
+
+impl Error for Overflow {}
+
+/// A history of values along with its table of differences, which together describe the
+/// lowest degree polynomial passing through every value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    /// The values followed by each row of differences, stopping before the first row which is
+    /// all zeros. A history which never reaches zeros ends with a row of a single value.
+    table: Vec<Vec<i128>>,
+}
+
+impl Sequence {
+    pub fn new(values: &[i64]) -> Result<Self, Overflow> {
+        let mut table = Vec::new();
+        let mut row = values.iter().map(|&v| i128::from(v)).collect::<Vec<i128>>();
+        while !row.is_empty() && row.iter().any(|&v| v != 0) {
+            let next = row
+                .windows(2)
+                .map(|w| w[1].checked_sub(w[0]).ok_or(Overflow))
+                .collect::<Result<Vec<i128>, Overflow>>()?;
+            table.push(row);
+            row = next;
+        }
+        Ok(Self { table })
+    }
+
+    /// Whether the differences reach a row of all zeros, rather than running out of values.
+    pub fn settles(&self) -> bool {
+        self.table.last().is_none_or(|row| row.len() > 1)
+    }
+
+    /// Degree of the polynomial through the values, `None` if they are all zero.
+    pub fn degree(&self) -> Option<usize> {
+        self.table.len().checked_sub(1)
+    }
+
+    /// The value at any index, counting the first value as 0, from Newton's forward
+    /// difference formula (https://en.wikipedia.org/wiki/Newton_polynomial).
+    pub fn evaluate(&self, index: i64) -> Result<i64, Overflow> {
+        let x = i128::from(index);
+        let mut binomial = 1i128;
+        let mut value = 0i128;
+        for (k, row) in self.table.iter().enumerate() {
+            if k > 0 {
+                // C(x, k) = C(x, k - 1) * (x - k + 1) / k, which divides exactly
+                let k = k as i128;
+                binomial = binomial.checked_mul(x - k + 1).ok_or(Overflow)? / k;
+            }
+            let term = binomial.checked_mul(row[0]).ok_or(Overflow)?;
+            value = value.checked_add(term).ok_or(Overflow)?;
+        }
+        i64::try_from(value).map_err(|_| Overflow)
+    }
+
+    /// The next `steps` values after the last.
+    pub fn forward(&self, steps: usize) -> Result<Vec<i64>, Overflow> {
+        let ends = self.table.iter().map(|row| row[row.len() - 1]).collect();
+        extend(ends, steps, i128::checked_add)
+    }
+
+    /// The `steps` values before the first, nearest first.
+    pub fn backward(&self, steps: usize) -> Result<Vec<i64>, Overflow> {
+        let starts = self.table.iter().map(|row| row[0]).collect();
+        extend(starts, steps, i128::checked_sub)
+    }
+
+    pub fn next_value(&self) -> Result<i64, Overflow> {
+        Ok(self.forward(1)?[0])
+    }
+
+    pub fn previous_value(&self) -> Result<i64, Overflow> {
+        Ok(self.backward(1)?[0])
+    }
+}
+
+/// Grow the table from one edge of each row, each row taking its next value from `step`
+/// applied to the row below. The bottom row is constant.
+fn extend(
+    mut edge: Vec<i128>,
+    steps: usize,
+    step: fn(i128, i128) -> Option<i128>,
+) -> Result<Vec<i64>, Overflow> {
+    let mut values = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        for row in (0..edge.len().saturating_sub(1)).rev() {
+            edge[row] = step(edge[row], edge[row + 1]).ok_or(Overflow)?;
+        }
+        let value = edge.first().copied().unwrap_or(0);
+        values.push(i64::try_from(value).map_err(|_| Overflow)?);
+    }
+    Ok(values)
+}
+
 #[aoc_generator(day9)]
-pub fn parse(input: &str) -> Vec<Vec<i32>> {
-    input
-        .lines()
+pub fn parse(input: &str) -> Result<Vec<Sequence>, ParseError> {
+    parser::lines(9, input)
         .map(|line| {
-            line.split_whitespace()
-                .map(|n| n.parse::<i32>().expect("Failed to parse input"))
-                .collect()
+            let values = line
+                .text
+                .split_whitespace()
+                .map(|n| line.parse::<i64>(n, "expected a number"))
+                .collect::<Result<Vec<i64>, ParseError>>()?;
+            if values.is_empty() {
+                return Err(line.missing("expected a number"));
+            }
+            Sequence::new(&values).map_err(|_| line.error(line.text, "differences overflowed"))
         })
         .collect()
 }
 
-#[aoc(day9, part1)]
-pub fn part1(input: &[Vec<i32>]) -> i32 {
-    input.iter().map(extrapolate).sum()
-}
-
-#[aoc(day9, part2)]
-pub fn part2(input: &[Vec<i32>]) -> i32 {
+/// Sum of one extrapolated value from each history.
+fn total(input: &[Sequence], value: fn(&Sequence) -> Result<i64, Overflow>) -> i64 {
     input
         .iter()
-        .map(|history| extrapolate(&history.iter().rev().cloned().collect()))
-        .sum()
+        .try_fold(0i64, |acc, sequence| acc.checked_add(value(sequence).ok()?))
+        .expect("Extrapolation overflowed")
 }
 
-#[allow(clippy::ptr_arg)]
-fn extrapolate(history: &Vec<i32>) -> i32 {
-    let diffs = history
-        .windows(2)
-        .map(|window| window[1] - window[0])
-        .collect::<Vec<i32>>();
-    let last_history = *history.last().expect("Expected history to contain a value");
-    if diffs.iter().all(|diff| *diff == 0) {
-        last_history
-    } else {
-        last_history + extrapolate(&diffs)
-    }
+#[aoc(day9, part1)]
+pub fn part1(input: &[Sequence]) -> i64 {
+    total(input, Sequence::next_value)
+}
+
+#[aoc(day9, part2)]
+pub fn part2(input: &[Sequence]) -> i64 {
+    total(input, Sequence::previous_value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use test_case::test_case;
 
     #[test]
     fn both_test() {
@@ -48,15 +151,66 @@ mod tests {
             "0 3 6 9 12 15
             1 3 6 10 15 21
             10 13 16 21 30 45",
-        );
+        )
+        .unwrap();
         assert_eq!(part1(&input), 114);
         assert_eq!(part2(&input), 2);
     }
 
+    #[test_case(&[0, 3, 6, 9, 12, 15], true, Some(1), &[18, 21, 24], &[-3, -6, -9] ; "linear")]
+    #[test_case(&[10, 13, 16, 21, 30, 45], true, Some(3), &[68, 101, 146], &[5, -4, -19] ; "cubic")]
+    #[test_case(&[0, 0, 0], true, None, &[0, 0], &[0, 0] ; "zeros")]
+    #[test_case(&[7], false, Some(0), &[7, 7], &[7, 7] ; "single value")]
+    #[test_case(&[1, 2, 4, 8, 16], false, Some(4), &[31, 57], &[1, 3] ; "powers of two")]
+    #[test_case(&[3, -1, 4, 1, -5], false, Some(4), &[13, 104], &[55, 216] ; "irregular")]
+    fn extrapolate(
+        values: &[i64],
+        settles: bool,
+        degree: Option<usize>,
+        forward: &[i64],
+        backward: &[i64],
+    ) {
+        let sequence = Sequence::new(values).unwrap();
+        assert_eq!(sequence.settles(), settles);
+        assert_eq!(sequence.degree(), degree);
+        assert_eq!(sequence.forward(forward.len()).unwrap(), forward);
+        assert_eq!(sequence.backward(backward.len()).unwrap(), backward);
+
+        // The fitted polynomial agrees with the history and both extrapolations
+        for (i, &value) in values.iter().enumerate() {
+            assert_eq!(sequence.evaluate(i as i64), Ok(value));
+        }
+        for (i, &value) in forward.iter().enumerate() {
+            assert_eq!(sequence.evaluate((values.len() + i) as i64), Ok(value));
+        }
+        for (i, &value) in backward.iter().enumerate() {
+            assert_eq!(sequence.evaluate(-1 - i as i64), Ok(value));
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        let sequence = Sequence::new(&[i64::MAX - 1, i64::MAX]).unwrap();
+        assert_eq!(sequence.forward(1), Err(Overflow));
+        assert_eq!(sequence.previous_value(), Ok(i64::MAX - 2));
+
+        // Differences only fit because they are worked out in i128
+        let sequence = Sequence::new(&[i64::MIN, i64::MAX, i64::MIN]).unwrap();
+        assert_eq!(sequence.degree(), Some(2));
+        assert_eq!(sequence.next_value(), Err(Overflow));
+        assert_eq!(sequence.evaluate(2), Ok(i64::MIN));
+
+        let sequence = Sequence::new(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(
+            sequence.evaluate(3_000_000_000),
+            Ok(9_000_000_000_000_000_000)
+        );
+        assert_eq!(sequence.evaluate(4_000_000_000), Err(Overflow));
+    }
+
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(9));
-        assert_eq!(part1(&input), 1974232246);
-        assert_eq!(part2(&input), 928);
+    fn parse_error() {
+        let err = parse("0 3 6 9 12 15\n1 3 6 10 15 2l").unwrap_err();
+        assert_eq!(err, ParseError::new(9, 2, 13, "2l", "expected a number"));
     }
 }
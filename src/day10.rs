@@ -1,26 +1,11 @@
-use std::collections::HashMap;
-
-type Point = (i32, i32);
-
-enum Heading {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Heading {
-    pub fn from(&self, loc: Point) -> Point {
-        match self {
-            Heading::North => (loc.0 - 1, loc.1),
-            Heading::East => (loc.0, loc.1 + 1),
-            Heading::South => (loc.0 + 1, loc.1),
-            Heading::West => (loc.0, loc.1 - 1),
-        }
-    }
-}
-
-#[derive(PartialEq)]
+use crate::{
+    direction::Direction,
+    geometry::Polygon,
+    grid::{Grid, Point},
+    parser::ParseError,
+};
+
+#[derive(Debug, PartialEq)]
 enum Route {
     NorthAndSouth,
     EastAndWest,
@@ -32,12 +17,13 @@ enum Route {
     Start,
 }
 
+#[derive(Debug)]
 struct Pipe {
     route: Route,
 }
 
 impl Pipe {
-    pub fn new(symbol: char) -> Self {
+    pub fn new(symbol: char) -> Option<Self> {
         let route = match symbol {
             '|' => Route::NorthAndSouth,
             '-' => Route::EastAndWest,
@@ -46,75 +32,58 @@ impl Pipe {
             '7' => Route::SouthAndWest,
             'F' => Route::SouthAndEast,
             'S' => Route::Start,
-            _ => Route::Ground,
+            '.' => Route::Ground,
+            _ => return None,
         };
-        Self { route }
+        Some(Self { route })
     }
 
-    pub fn get_heading(&self, heading: &Heading) -> Option<Heading> {
+    pub fn get_heading(&self, heading: Direction) -> Option<Direction> {
         match heading {
-            Heading::North => match self.route {
-                Route::NorthAndSouth => Some(Heading::North),
-                Route::SouthAndWest => Some(Heading::West),
-                Route::SouthAndEast => Some(Heading::East),
+            Direction::North => match self.route {
+                Route::NorthAndSouth => Some(Direction::North),
+                Route::SouthAndWest => Some(Direction::West),
+                Route::SouthAndEast => Some(Direction::East),
                 _ => None,
             },
-            Heading::East => match self.route {
-                Route::EastAndWest => Some(Heading::East),
-                Route::NorthAndWest => Some(Heading::North),
-                Route::SouthAndWest => Some(Heading::South),
+            Direction::East => match self.route {
+                Route::EastAndWest => Some(Direction::East),
+                Route::NorthAndWest => Some(Direction::North),
+                Route::SouthAndWest => Some(Direction::South),
                 _ => None,
             },
-            Heading::South => match self.route {
-                Route::NorthAndSouth => Some(Heading::South),
-                Route::NorthAndEast => Some(Heading::East),
-                Route::NorthAndWest => Some(Heading::West),
+            Direction::South => match self.route {
+                Route::NorthAndSouth => Some(Direction::South),
+                Route::NorthAndEast => Some(Direction::East),
+                Route::NorthAndWest => Some(Direction::West),
                 _ => None,
             },
-            Heading::West => match self.route {
-                Route::EastAndWest => Some(Heading::West),
-                Route::NorthAndEast => Some(Heading::North),
-                Route::SouthAndEast => Some(Heading::South),
+            Direction::West => match self.route {
+                Route::EastAndWest => Some(Direction::West),
+                Route::NorthAndEast => Some(Direction::North),
+                Route::SouthAndEast => Some(Direction::South),
                 _ => None,
             },
         }
     }
 }
 
-/// Shoelace Formula (https://en.wikipedia.org/wiki/Shoelace_formula).
-/// The starting point must be present at the start and the end of the outline
-pub fn shoelace(outline: &[Point]) -> usize {
-    let sum = outline.windows(2).fold(0, |acc, matrix| {
-        acc + (matrix[0].0 * matrix[1].1) - (matrix[1].0 * matrix[0].1)
-    });
-    // If the points are labeled sequentially in the counterclockwise direction, then the area is positive,
-    // if they are labeled in the clockwise direction, the area will be negative.
-    (sum.abs() / 2) as usize
-}
-
-type Points = HashMap<(i32, i32), Pipe>;
-
-#[derive(Default)]
+#[derive(Debug)]
 pub struct Map {
-    points: Points,
-    start: Option<Point>,
+    points: Grid<Pipe>,
+    start: Point,
 }
 
 #[aoc_generator(day10)]
-pub fn parse(input: &str) -> Map {
-    let mut map = Map::default();
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            let pipe = Pipe::new(c);
-            if pipe.route == Route::Start {
-                map.start = Some((row as i32, col as i32));
-            }
-            map.points.insert((row as i32, col as i32), pipe);
-        }
-    }
-
-    map
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    let points = Grid::parse(10, input, |c| Pipe::new(c).ok_or("unknown pipe"))?;
+    let start = points
+        .iter()
+        .find(|(_, pipe)| pipe.route == Route::Start)
+        .map(|(pos, _)| pos)
+        .ok_or_else(|| ParseError::new(10, 1, 1, "", "expected a starting position 'S'"))?;
+
+    Ok(Map { points, start })
 }
 
 #[aoc(day10, part1)]
@@ -129,13 +98,15 @@ pub fn part2(map: &Map) -> usize {
 
 fn solve(map: &Map) -> (i32, usize) {
     // Find a valid route from the start
-    let start = map.start.expect("No starting coord");
-    let mut heading = Heading::North;
-
-    for h in [Heading::North, Heading::East, Heading::South, Heading::West] {
-        let next_loc = h.from(start);
-        if let Some(point) = map.points.get(&next_loc) {
-            if point.get_heading(&h).is_some() {
+    let start = map.start;
+    let mut heading = Direction::North;
+
+    for h in Direction::ALL {
+        if let Some(point) = h
+            .step(start, 1)
+            .and_then(|next_loc| map.points.get(next_loc))
+        {
+            if point.get_heading(h).is_some() {
                 heading = h;
                 break;
             }
@@ -143,27 +114,29 @@ fn solve(map: &Map) -> (i32, usize) {
     }
 
     let mut steps = 1;
-    let mut loc = heading.from(map.start.unwrap());
-    let mut pipe = map.points.get(&loc).expect("No pipe at location");
+    let mut loc = heading.step(start, 1).expect("No pipe at location");
+    let mut pipe = &map.points[loc];
     let mut outline = Vec::from([start, loc]);
     while pipe.route != Route::Start {
-        heading = pipe.get_heading(&heading).expect("Heading not valid");
-        loc = heading.from(loc);
-        pipe = map.points.get(&loc).expect("No point at location");
+        heading = pipe.get_heading(heading).expect("Heading not valid");
+        loc = heading.step(loc, 1).expect("No point at location");
+        pipe = map.points.get(loc).expect("No point at location");
         steps += 1;
         outline.push(loc);
     }
-    let area = shoelace(&outline);
-    // Pick's Theorem (https://en.wikipedia.org/wiki/Pick%27s_theorem)
-    let interior_points = area - (outline.len() - 1) / 2 + 1;
-
-    (steps / 2, interior_points)
+    let interior_points = outline
+        .into_iter()
+        .map(|(row, col)| (row as i64, col as i64))
+        .collect::<Polygon>()
+        .interior_points()
+        .expect("Loop too large to count");
+
+    (steps / 2, interior_points as usize)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
     use test_case::test_case;
 
     #[test_case(
@@ -223,18 +196,37 @@ mod tests {
         10
         ;"e4"
     )]
+    #[test_case(
+        "
+        S7
+        LJ
+        ",
+        2,
+        0
+        ;"smallest loop"
+    )]
+    #[test_case(
+        "
+        S---7
+        |F-7|
+        LJ.LJ
+        ",
+        7,
+        0
+        ;"loop alongside itself"
+    )]
     fn both_test(input: &str, part_1: i32, part_2: usize) {
-        let input = parse(input);
+        let input = parse(input).unwrap();
         let result = solve(&input);
         assert_eq!(result.0, part_1);
         assert_eq!(result.1, part_2);
     }
 
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(10));
-        let result = solve(&input);
-        assert_eq!(result.0, 6927);
-        assert_eq!(result.1, 467);
+    fn parse_error() {
+        let err = parse(".S-7.\n.|.|.\n.L-J?").unwrap_err();
+        assert_eq!(err, ParseError::new(10, 3, 5, "?", "unknown pipe"));
+        let err = parse(".F-7.\n.|.|.\n.L-J.").unwrap_err();
+        assert_eq!(err.reason, "expected a starting position 'S'");
     }
 }
@@ -1,83 +1,220 @@
-use crate::parser;
-
-pub fn solve(input: &[String], expansion_factor: usize) -> usize {
-    let mut image = Vec::new();
-    let mut empty_row = Vec::new();
-    let mut empty_col = Vec::new();
-    let width = input.first().expect("Input is empty").len();
-
-    input.iter().enumerate().for_each(|(row, line)| {
-        let mut empty_space = 0;
-        line.chars().enumerate().for_each(|(col, c)| {
-            if c == '#' {
-                image.push((col, row));
-            } else {
-                empty_space += 1;
-            }
-        });
-        if empty_space == width {
-            empty_row.push(row);
-        }
-    });
+use std::{error::Error, fmt};
 
-    // Check for empty columns
-    for col in 0..width {
-        if !image.iter().any(|(c, _)| *c == col) {
-            empty_col.push(col);
-        }
+use crate::{grid::Grid, parser::ParseError};
+
+/// A size or distance which doesn't fit in a `u128`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expanded universe is too large")
+    }
+}
+
+impl Error for Overflow {}
+
+/// Coordinates along one axis once every empty line has grown to `factor` lines, and the
+/// expanded length of the axis. `occupied` says whether each line holds a galaxy.
+fn expand(occupied: &[bool], factor: u128) -> Result<(Vec<u128>, u128), Overflow> {
+    let mut expanded = Vec::with_capacity(occupied.len());
+    let mut position = 0u128;
+    for &line in occupied {
+        expanded.push(position);
+        position = position
+            .checked_add(if line { 1 } else { factor })
+            .ok_or(Overflow)?;
+    }
+    Ok((expanded, position))
+}
+
+/// Sum of the distances between every pair of coordinates along one axis. Once sorted, the
+/// gap after the coordinate at index `k` is crossed by each pair with one of the `k + 1`
+/// up to it and one of the rest. Every term adds to the total, so this only overflows when
+/// the total itself doesn't fit.
+fn pairwise_sum(mut coordinates: Vec<u128>) -> Result<u128, Overflow> {
+    coordinates.sort_unstable();
+    let n = coordinates.len() as u128;
+    coordinates
+        .windows(2)
+        .zip(1..)
+        .try_fold(0u128, |sum, (pair, before)| {
+            let pairs = before * (n - before);
+            (pair[1] - pair[0])
+                .checked_mul(pairs)
+                .and_then(|distance| sum.checked_add(distance))
+        })
+        .ok_or(Overflow)
+}
+
+/// Largest expanded map, in cells, which [`Universe::render`] will draw.
+pub const RENDER_LIMIT: u128 = 1 << 20;
+
+/// The galaxies of an image once empty space has expanded. Galaxies are numbered from 1 in
+/// reading order, as in the puzzle text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Universe {
+    /// Expanded `(row, col)` of each galaxy, galaxy `n` at index `n - 1`
+    galaxies: Vec<(u128, u128)>,
+    height: u128,
+    width: u128,
+}
+
+impl Universe {
+    /// Expand an image, with each empty row growing to `row_factor` rows and each empty column
+    /// to `col_factor` columns. Fails if the height and width together don't fit in a `u128`,
+    /// which keeps every distance between two galaxies in range.
+    pub fn new(image: &Grid<bool>, row_factor: u128, col_factor: u128) -> Result<Self, Overflow> {
+        let rows = image
+            .rows()
+            .map(|r| r.contains(&true))
+            .collect::<Vec<bool>>();
+        let cols = image
+            .columns()
+            .map(|mut c| c.any(|&galaxy| galaxy))
+            .collect::<Vec<bool>>();
+        let (rows, height) = expand(&rows, row_factor)?;
+        let (cols, width) = expand(&cols, col_factor)?;
+        height.checked_add(width).ok_or(Overflow)?;
+
+        let galaxies = image
+            .iter()
+            .filter(|(_, &galaxy)| galaxy)
+            .map(|((row, col), _)| (rows[row], cols[col]))
+            .collect();
+        Ok(Self {
+            galaxies,
+            height,
+            width,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.galaxies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.galaxies.is_empty()
+    }
+
+    /// Expanded `(row, col)` of a galaxy.
+    pub fn galaxy(&self, id: usize) -> Option<(u128, u128)> {
+        id.checked_sub(1)
+            .and_then(|i| self.galaxies.get(i))
+            .copied()
+    }
+
+    /// Each galaxy's ID and expanded `(row, col)`.
+    pub fn galaxies(&self) -> impl Iterator<Item = (usize, (u128, u128))> + '_ {
+        (1..).zip(self.galaxies.iter().copied())
+    }
+
+    /// Taxicab distance (https://en.wikipedia.org/wiki/Taxicab_geometry) between two galaxies.
+    pub fn distance(&self, a: usize, b: usize) -> Option<u128> {
+        let (a, b) = (self.galaxy(a)?, self.galaxy(b)?);
+        Some(a.0.abs_diff(b.0) + a.1.abs_diff(b.1))
+    }
+
+    /// Distance between every pair of galaxies, with galaxy `n` at row and column `n - 1`.
+    pub fn distances(&self) -> Vec<Vec<u128>> {
+        (1..=self.len())
+            .map(|a| {
+                (1..=self.len())
+                    .map(|b| self.distance(a, b).unwrap_or_default())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The closest other galaxy and its distance, the lowest ID on a tie.
+    pub fn nearest(&self, id: usize) -> Option<(usize, u128)> {
+        self.others(id)
+            .min_by_key(|&(other, distance)| (distance, other))
+    }
+
+    /// The farthest other galaxy and its distance, the lowest ID on a tie.
+    pub fn farthest(&self, id: usize) -> Option<(usize, u128)> {
+        self.others(id)
+            .min_by_key(|&(other, distance)| (std::cmp::Reverse(distance), other))
+    }
+
+    fn others(&self, id: usize) -> impl Iterator<Item = (usize, u128)> + '_ {
+        (1..=self.len())
+            .filter(move |&other| other != id)
+            .filter_map(move |other| Some((other, self.distance(id, other)?)))
+    }
+
+    /// Sum of the distances between every pair of galaxies. Distances along each axis are
+    /// independent, so they are summed separately.
+    pub fn total_distance(&self) -> Result<u128, Overflow> {
+        let rows = pairwise_sum(self.galaxies.iter().map(|&(row, _)| row).collect())?;
+        let cols = pairwise_sum(self.galaxies.iter().map(|&(_, col)| col).collect())?;
+        rows.checked_add(cols).ok_or(Overflow)
     }
 
-    // Taxicab distance (https://en.wikipedia.org/wiki/Taxicab_geometry)
-    let mut distances = Vec::new();
-    for i in 0..image.len() - 1 {
-        for j in i + 1..image.len() {
-            let p1 = image[i];
-            let p2 = image[j];
-            let (col_min, col_max) = (p1.0.min(p2.0), p1.0.max(p2.0));
-            let (row_min, row_max) = (p1.1.min(p2.1), p1.1.max(p2.1));
-            let col_diff = col_max - col_min;
-            let row_diff = row_max - row_min;
-            // Add row and col expansions
-            let col_expansion = empty_col
-                .iter()
-                .filter(|c| **c >= col_min && **c <= col_max)
-                .count();
-            let row_expansion = empty_row
-                .iter()
-                .filter(|r| **r >= row_min && **r <= row_max)
-                .count();
-            let distance = col_diff
-                + col_expansion * (expansion_factor - 1)
-                + row_diff
-                + row_expansion * (expansion_factor - 1);
-            distances.push(distance);
+    /// The expanded map as in the puzzle text, or `None` if it has more than [`RENDER_LIMIT`]
+    /// cells.
+    pub fn render(&self) -> Option<String> {
+        if self.height.checked_mul(self.width)? > RENDER_LIMIT {
+            return None;
+        }
+        let (height, width) = (self.height as usize, self.width as usize);
+        let mut map = vec![vec!['.'; width]; height];
+        for &(row, col) in &self.galaxies {
+            map[row as usize][col as usize] = '#';
         }
+        Some(
+            map.into_iter()
+                .map(|row| row.into_iter().collect::<String>() + "\n")
+                .collect(),
+        )
     }
+}
 
-    distances.iter().sum::<usize>()
+/// Sum of the distances between every pair of galaxies, with each empty row growing to
+/// `row_factor` rows and each empty column to `col_factor` columns.
+pub fn solve(image: &Grid<bool>, row_factor: u128, col_factor: u128) -> Result<u128, Overflow> {
+    Universe::new(image, row_factor, col_factor)?.total_distance()
 }
 
 #[aoc_generator(day11)]
-pub fn parse(input: &str) -> Vec<String> {
-    parser::test_input(input)
+pub fn parse(input: &str) -> Result<Grid<bool>, ParseError> {
+    Grid::parse(11, input, |c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err("expected . or #"),
+    })
 }
 
 #[aoc(day11, part1)]
-pub fn part1(input: &[String]) -> usize {
-    solve(input, 2)
+pub fn part1(input: &Grid<bool>) -> u128 {
+    solve(input, 2, 2).expect("Distances overflowed")
 }
 
 #[aoc(day11, part2)]
-pub fn part2(input: &[String]) -> usize {
-    solve(input, 1000000)
+pub fn part2(input: &Grid<bool>) -> u128 {
+    solve(input, 1000000, 1000000).expect("Distances overflowed")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use crate::grid::Point;
     use test_case::test_case;
 
+    const TEST: &str = "
+        ...#......
+        .......#..
+        #.........
+        ..........
+        ......#...
+        .#........
+        .........#
+        ..........
+        .......#..
+        #...#.....
+    ";
+
     #[test_case(
         "
         ...#......
@@ -96,17 +233,144 @@ mod tests {
         8410
         ;"e1"
     )]
-    fn both_test(input: &str, answer_1: usize, answer_2: usize, answer_3: usize) {
-        let data = parse(input);
-        assert_eq!(solve(&data, 2), answer_1);
-        assert_eq!(solve(&data, 10), answer_2);
-        assert_eq!(solve(&data, 100), answer_3);
+    fn both_test(input: &str, answer_1: u128, answer_2: u128, answer_3: u128) {
+        let data = parse(input).unwrap();
+        assert_eq!(solve(&data, 2, 2), Ok(answer_1));
+        assert_eq!(solve(&data, 10, 10), Ok(answer_2));
+        assert_eq!(solve(&data, 100, 100), Ok(answer_3));
+    }
+
+    /// Every pair of galaxies, counting the empty lines between them.
+    fn brute_force(image: &Grid<bool>, row_factor: u128, col_factor: u128) -> u128 {
+        let galaxies = image
+            .iter()
+            .filter(|(_, &g)| g)
+            .map(|(pos, _)| pos)
+            .collect::<Vec<Point>>();
+        let empty_rows = (0..image.height())
+            .filter(|&r| !image.row(r).contains(&true))
+            .collect::<Vec<usize>>();
+        let empty_cols = (0..image.width())
+            .filter(|&c| !image.column(c).any(|&g| g))
+            .collect::<Vec<usize>>();
+        let mut sum = 0;
+        for (i, a) in galaxies.iter().enumerate() {
+            for b in &galaxies[i + 1..] {
+                let (r0, r1) = (a.0.min(b.0), a.0.max(b.0));
+                let (c0, c1) = (a.1.min(b.1), a.1.max(b.1));
+                let rows = empty_rows.iter().filter(|r| (r0..r1).contains(r)).count();
+                let cols = empty_cols.iter().filter(|c| (c0..c1).contains(c)).count();
+                sum += (r1 - r0) as u128
+                    + rows as u128 * (row_factor - 1)
+                    + (c1 - c0) as u128
+                    + cols as u128 * (col_factor - 1);
+            }
+        }
+        sum
+    }
+
+    #[test_case(2, 10 ; "wider than tall")]
+    #[test_case(1000, 1 ; "only rows")]
+    #[test_case(1, 1 ; "no expansion")]
+    fn factor_per_axis(row_factor: u128, col_factor: u128) {
+        let image = parse(TEST).unwrap();
+        assert_eq!(
+            solve(&image, row_factor, col_factor),
+            Ok(brute_force(&image, row_factor, col_factor))
+        );
+    }
+
+    #[test]
+    fn large_factor() {
+        let image = parse(TEST).unwrap();
+        let factor = u64::MAX as u128;
+        // Each pair crossing an empty line gains `factor - 1` for it
+        assert_eq!(solve(&image, factor, factor), Ok(374 + (factor - 2) * 82));
+    }
+
+    #[test]
+    fn overflow() {
+        let image = parse(TEST).unwrap();
+        // 2 empty rows and 3 empty columns, so the universe is 8 + 2f by 7 + 3f
+        let factor = u128::MAX / 5;
+        assert_eq!(Universe::new(&image, factor, factor), Err(Overflow));
+        assert_eq!(Universe::new(&image, u128::MAX, 1), Err(Overflow));
+
+        // Every distance fits, but their total doesn't
+        let universe = Universe::new(&image, u128::MAX / 10, u128::MAX / 10).unwrap();
+        assert!(universe.distance(1, 9).is_some());
+        assert_eq!(universe.total_distance(), Err(Overflow));
+    }
+
+    #[test_case(5, 9, 9 ; "5 to 9")]
+    #[test_case(1, 7, 15 ; "1 to 7")]
+    #[test_case(3, 6, 17 ; "3 to 6")]
+    #[test_case(8, 9, 5 ; "8 to 9")]
+    fn pair_distance(a: usize, b: usize, expected: u128) {
+        let universe = Universe::new(&parse(TEST).unwrap(), 2, 2).unwrap();
+        assert_eq!(universe.distance(a, b), Some(expected));
+        assert_eq!(universe.distance(b, a), Some(expected));
+    }
+
+    #[test]
+    fn universe() {
+        let universe = Universe::new(&parse(TEST).unwrap(), 2, 2).unwrap();
+        assert_eq!(universe.len(), 9);
+        assert_eq!(universe.galaxy(1), Some((0, 4)));
+        assert_eq!(universe.galaxy(9), Some((11, 5)));
+        assert_eq!(universe.galaxy(0), None);
+        assert_eq!(universe.galaxy(10), None);
+
+        let distances = universe.distances();
+        assert_eq!(distances[4][8], 9);
+        assert!((0..9).all(|i| distances[i][i] == 0));
+        let sum = distances.iter().flatten().sum::<u128>();
+        assert_eq!(Ok(sum), universe.total_distance().map(|t| 2 * t));
+
+        assert_eq!(universe.nearest(5), Some((3, 5)));
+        assert_eq!(universe.nearest(8), Some((9, 5)));
+        // Galaxies 6, 7 and 8 are all 15 away
+        assert_eq!(universe.farthest(1), Some((6, 15)));
+        assert_eq!(universe.nearest(10), None);
+        assert_eq!(universe.farthest(0), None);
+    }
+
+    #[test]
+    fn render() {
+        let universe = Universe::new(&parse(TEST).unwrap(), 2, 2).unwrap();
+        assert_eq!(
+            universe.render().unwrap(),
+            "\
+....#........
+.........#...
+#............
+.............
+.............
+........#....
+.#...........
+............#
+.............
+.............
+.........#...
+#....#.......
+"
+        );
+        assert_eq!(
+            Universe::new(&parse(TEST).unwrap(), 1000000, 1)
+                .unwrap()
+                .render(),
+            None
+        );
     }
 
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(11));
-        assert_eq!(part1(&input), 9648398);
-        assert_eq!(part2(&input), 618800410814);
+    fn parse_error() {
+        let err = parse("...#\n.x..").unwrap_err();
+        assert_eq!(err, ParseError::new(11, 2, 2, "x", "expected . or #"));
+        let err = parse("...#\n.#.").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(11, 2, 4, "", "expected all rows to be the same width")
+        );
     }
 }
@@ -1,147 +1,563 @@
-use std::collections::HashMap;
+use std::{error::Error, fmt};
 
-use crate::parser;
+use rand::{Rng, RngExt};
+use rayon::prelude::*;
 
-struct Record {
-    springs: String,
-    groups: Vec<usize>,
+use crate::parser::{self, ParseError};
+
+/// Too many arrangements to count in a `u128`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "too many arrangements to count")
+    }
 }
 
-fn parse_input(input: &Vec<String>, repeat: usize) -> Vec<Record> {
-    let mut records = Vec::new();
-    for line in input {
-        let split = line.split(' ').collect::<Vec<&str>>();
-        let springs = split
-            .first()
-            .expect("Expected condition records for springs")
-            .split(',') // bit of a hack to get an iterator
-            .collect::<Vec<&str>>()
-            .repeat(repeat)
-            .join("?");
-        let groups = split
-            .last()
-            .expect("Expected spring arrangements")
-            .split(',')
-            .map(|x| x.parse::<usize>().expect("Failed to parse arrangement"))
-            .collect::<Vec<usize>>()
-            .repeat(repeat);
-        records.push(Record { springs, groups });
+impl Error for Overflow {}
+
+/// The condition of a single spring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spring {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+impl Spring {
+    /// How the spring is written in a record.
+    pub fn symbol(self) -> char {
+        match self {
+            Spring::Operational => '.',
+            Spring::Damaged => '#',
+            Spring::Unknown => '?',
+        }
     }
-    records
 }
 
-/// Solving using top-down Dynamic Programming
-fn solve(
-    springs: &str,
-    groups: &[usize],
-    memo: &mut HashMap<(String, Vec<usize>), usize>,
-) -> usize {
-    // If there are no more arrangements left, we've reached the end.
-    // There must be no broken springs ('#') remaining for this to be a solution.
-    if groups.is_empty() {
-        if springs.contains('#') {
-            return 0;
-        } else {
-            return 1;
+/// How a record is unfolded: its springs are repeated `times` over with `separator` between
+/// each copy, and its groups repeated to match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unfold {
+    pub times: usize,
+    pub separator: Spring,
+}
+
+impl Unfold {
+    /// The record as written.
+    pub const FOLDED: Unfold = Unfold {
+        times: 1,
+        separator: Spring::Unknown,
+    };
+}
+
+/// A row of springs, each operational (`.`), damaged (`#`) or unknown (`?`), and the sizes of
+/// each contiguous group of damaged springs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub springs: String,
+    pub groups: Vec<usize>,
+}
+
+/// Counts of the arrangements of every suffix of a record, by bottom-up Dynamic
+/// Programming (https://en.wikipedia.org/wiki/Dynamic_programming).
+struct Table<'a> {
+    springs: &'a [u8],
+    groups: &'a [usize],
+    /// `ways[i * width + j]` is the number of arrangements of `springs[i..]` holding exactly
+    /// `groups[j..]`, kept in one flat table with a row per spring. `None` where there are
+    /// more than a `u128` holds, which only matters if that suffix can be reached.
+    ways: Vec<Option<u128>>,
+    width: usize,
+    /// Length of the run of possibly damaged springs starting at each index
+    run: Vec<usize>,
+}
+
+impl<'a> Table<'a> {
+    fn new(springs: &'a [u8], groups: &'a [usize]) -> Self {
+        let (n, m) = (springs.len(), groups.len());
+        let mut run = vec![0; n + 1];
+        for i in (0..n).rev() {
+            run[i] = if springs[i] == b'.' {
+                0
+            } else {
+                run[i + 1] + 1
+            };
+        }
+
+        let width = m + 1;
+        let mut table = Self {
+            springs,
+            groups,
+            ways: vec![Some(0); (n + 1) * width],
+            width,
+            run,
+        };
+        // With no springs left, only having no groups left works
+        table.ways[n * width + m] = Some(1);
+
+        for i in (0..n).rev() {
+            for j in 0..=m {
+                let (operational, damaged) = table.options(i, j);
+                let damaged = damaged.map_or(Some(0), |next| table.ways(next, j + 1));
+                table.ways[i * width + j] =
+                    operational.zip(damaged).and_then(|(a, b)| a.checked_add(b));
+            }
         }
+        table
     }
 
-    // Check whether we've previously calculated this solution
-    if let Some(res) = memo.get(&(springs.to_string(), groups.to_vec())) {
-        return *res;
+    fn ways(&self, i: usize, j: usize) -> Option<u128> {
+        self.ways[i * self.width + j]
     }
 
-    // Check whether the remaining groups can possibly be made from the remaining springs.
-    // Remember that each group must have an operational spring between them.
-    let length_required = groups.iter().sum::<usize>() + groups.len() - 1;
-    if springs.len() < length_required {
-        return 0;
+    /// Arrangements of `springs[i..]` and `groups[j..]` which treat spring `i` as operational,
+    /// then where the springs after group `j` begin if it can start at spring `i`.
+    fn options(&self, i: usize, j: usize) -> (Option<u128>, Option<usize>) {
+        let n = self.springs.len();
+        let operational = match self.springs[i] {
+            b'#' => Some(0),
+            _ => self.ways(i + 1, j),
+        };
+        // A group needs to fit in the run of springs which can be damaged, so ends within the
+        // springs, and be followed by their end or one which can be operational
+        let damaged =
+            (self.springs[i] != b'.' && j < self.groups.len() && self.run[i] >= self.groups[j])
+                .then(|| i + self.groups[j])
+                .filter(|&end| end == n || self.springs[end] != b'#')
+                .map(|end| n.min(end + 1));
+        (operational, damaged)
     }
 
-    // Skip this spring if it is operational. I find the str slice notation a little confusing
-    // but this is effectively checking whether index 0 is operational. If it is, check the
-    // remaining springs from index 1 onwards.
-    let current_spring = &springs[0..1];
-    if current_spring == "." {
-        return solve(&springs[1..], groups, memo);
+    /// The arrangement at `index` in lexicographic order, where `#` comes before `.`.
+    fn arrangement(&self, mut index: u128) -> Option<String> {
+        if index >= self.ways(0, 0)? {
+            return None;
+        }
+        let n = self.springs.len();
+        let mut arrangement = String::with_capacity(n);
+        let (mut i, mut j) = (0, 0);
+        while i < n {
+            // Every count along the way to an arrangement is within the total, so fits
+            let (_, damaged) = self.options(i, j);
+            let damaged = damaged.map(|next| (next, self.ways(next, j + 1).unwrap_or(u128::MAX)));
+            match damaged {
+                Some((next, ways)) if index < ways => {
+                    arrangement.extend(std::iter::repeat_n('#', self.groups[j]));
+                    arrangement.extend(std::iter::repeat_n('.', next - i - self.groups[j]));
+                    i = next;
+                    j += 1;
+                }
+                _ => {
+                    index -= damaged.map_or(0, |(_, ways)| ways);
+                    arrangement.push('.');
+                    i += 1;
+                }
+            }
+        }
+        Some(arrangement)
     }
+}
 
-    // Check whether the next damaged group can be placed at this position.
-    // For this to hold true, it must not have any operational springs ('.').
-    let group = *groups.first().expect("Groups unexpectedly empty");
-    let all_broken = !springs[0..group].contains(".");
+/// Number of ways the unknown springs can be filled in to match the groups.
+pub fn count(springs: &[u8], groups: &[usize]) -> Result<u128, Overflow> {
+    Table::new(springs, groups).ways(0, 0).ok_or(Overflow)
+}
 
-    // Check that this is either the last group, or that the spring after this group
-    // is not explicitly broken as there must be at least one operational spring between broken groups.
-    let placement_valid = springs.len() == group || !springs[group..group + 1].contains("#");
+impl Record {
+    pub fn unfold(&self, unfold: Unfold) -> Record {
+        let copies = vec![self.springs.as_str(); unfold.times];
+        Record {
+            springs: copies.join(&unfold.separator.symbol().to_string()),
+            groups: self.groups.repeat(unfold.times),
+        }
+    }
+
+    pub fn count(&self) -> Result<u128, Overflow> {
+        count(self.springs.as_bytes(), &self.groups)
+    }
+
+    /// Arrangements of the record unfolded 1 to `max` times, the first for the record as
+    /// written.
+    pub fn growth(&self, separator: Spring, max: usize) -> Vec<Result<u128, Overflow>> {
+        (1..=max)
+            .map(|times| self.unfold(Unfold { times, separator }).count())
+            .collect()
+    }
+
+    fn table(&self) -> Result<Table<'_>, Overflow> {
+        let table = Table::new(self.springs.as_bytes(), &self.groups);
+        table.ways(0, 0).ok_or(Overflow)?;
+        Ok(table)
+    }
+
+    /// Up to `limit` of the arrangements, in lexicographic order, each worked out only when
+    /// it is needed.
+    pub fn arrangements(
+        &self,
+        limit: usize,
+    ) -> Result<impl Iterator<Item = String> + '_, Overflow> {
+        let table = self.table()?;
+        Ok((0..limit as u128).map_while(move |index| table.arrangement(index)))
+    }
 
-    // Consider this group is placed here. Find out whether the remaining groups fit into the
-    // remaining springs. Remembering to skip the next spring if we're not at the end.
-    let mut total = 0;
-    if all_broken && placement_valid {
-        let next_index = springs.len().min(group + 1);
-        total += solve(&springs[next_index..], &groups[1..], memo);
+    /// The arrangement at `index` in lexicographic order, where `#` comes before `.`.
+    pub fn arrangement(&self, index: u128) -> Result<Option<String>, Overflow> {
+        Ok(self.table()?.arrangement(index))
     }
 
-    // If this spring is '?' it could be operational, in which case we should skip it and
-    // consider whether these groups fit into the remaining springs.
-    if current_spring == "?" {
-        total += solve(&springs[1..], groups, memo);
+    /// An arrangement chosen uniformly at random, or `None` if there are none.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Option<String>, Overflow> {
+        let table = self.table()?;
+        Ok(match table.ways(0, 0) {
+            Some(0) | None => None,
+            Some(ways) => table.arrangement(rng.random_range(0..ways)),
+        })
     }
+}
 
-    // Add this answer to the memo
-    memo.insert((springs.to_string(), groups.to_vec()), total);
-    total
+/// Total arrangements across every record once unfolded, counted in parallel.
+pub fn total(records: &[Record], unfold: Unfold) -> Result<u128, Overflow> {
+    records
+        .par_iter()
+        .map(|record| record.unfold(unfold).count())
+        .try_reduce(|| 0, |a, b| a.checked_add(b).ok_or(Overflow))
 }
 
 #[aoc_generator(day12)]
-pub fn parse(input: &str) -> Vec<String> {
-    parser::test_input(input)
+pub fn parse(input: &str) -> Result<Vec<Record>, ParseError> {
+    parser::lines(12, input)
+        .map(|line| {
+            let mut split = line.text.split_whitespace();
+            let springs = split
+                .next()
+                .ok_or_else(|| line.missing("expected springs"))?;
+            if let Some(index) = springs.find(|c| !matches!(c, '.' | '#' | '?')) {
+                let len = springs[index..].chars().next().map_or(0, char::len_utf8);
+                let spring = &springs[index..index + len];
+                return Err(line.error(spring, "expected ., # or ?"));
+            }
+            let groups = split
+                .next()
+                .ok_or_else(|| line.missing("expected groups"))?
+                .split(',')
+                .map(
+                    |group| match line.parse::<usize>(group, "unable to parse group")? {
+                        0 => Err(line.error(group, "expected a group of at least one spring")),
+                        size => Ok(size),
+                    },
+                )
+                .collect::<Result<Vec<usize>, ParseError>>()?;
+            Ok(Record {
+                springs: springs.to_string(),
+                groups,
+            })
+        })
+        .collect()
 }
 
 #[aoc(day12, part1)]
-pub fn part1(input: &Vec<String>) -> usize {
-    let records_1 = parse_input(input, 1);
-    let mut part_1 = Vec::new();
-    for record in records_1 {
-        part_1.push(solve(&record.springs, &record.groups, &mut HashMap::new()));
-    }
-    part_1.iter().sum::<usize>()
+pub fn part1(input: &[Record]) -> u128 {
+    total(input, Unfold::FOLDED).expect("Too many arrangements to count")
 }
 
 #[aoc(day12, part2)]
-pub fn part2(input: &Vec<String>) -> usize {
-    let records_2 = parse_input(input, 5);
-    let mut part_2 = Vec::new();
-    for record in records_2 {
-        part_2.push(solve(&record.springs, &record.groups, &mut HashMap::new()));
-    }
-    part_2.iter().sum::<usize>()
+pub fn part2(input: &[Record]) -> u128 {
+    let unfold = Unfold {
+        times: 5,
+        separator: Spring::Unknown,
+    };
+    total(input, unfold).expect("Too many arrangements to count")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{collections::HashMap, time::Instant};
+    use test_case::test_case;
+
+    /// The original top-down Dynamic Programming solution, kept to check `count` against
+    fn solve(
+        springs: &str,
+        groups: &[usize],
+        memo: &mut HashMap<(String, Vec<usize>), usize>,
+    ) -> usize {
+        // If there are no more arrangements left, we've reached the end.
+        // There must be no broken springs ('#') remaining for this to be a solution.
+        if groups.is_empty() {
+            if springs.contains('#') {
+                return 0;
+            } else {
+                return 1;
+            }
+        }
+
+        // Check whether we've previously calculated this solution
+        if let Some(res) = memo.get(&(springs.to_string(), groups.to_vec())) {
+            return *res;
+        }
+
+        // Check whether the remaining groups can possibly be made from the remaining springs.
+        // Remember that each group must have an operational spring between them.
+        let length_required = groups.iter().sum::<usize>() + groups.len() - 1;
+        if springs.len() < length_required {
+            return 0;
+        }
+
+        // Skip this spring if it is operational. I find the str slice notation a little confusing
+        // but this is effectively checking whether index 0 is operational. If it is, check the
+        // remaining springs from index 1 onwards.
+        let current_spring = &springs[0..1];
+        if current_spring == "." {
+            return solve(&springs[1..], groups, memo);
+        }
+
+        // Check whether the next damaged group can be placed at this position.
+        // For this to hold true, it must not have any operational springs ('.').
+        let group = *groups.first().expect("Groups unexpectedly empty");
+        let all_broken = !springs[0..group].contains(".");
+
+        // Check that this is either the last group, or that the spring after this group
+        // is not explicitly broken as there must be at least one operational spring between broken groups.
+        let placement_valid = springs.len() == group || !springs[group..group + 1].contains("#");
+
+        // Consider this group is placed here. Find out whether the remaining groups fit into the
+        // remaining springs. Remembering to skip the next spring if we're not at the end.
+        let mut total = 0;
+        if all_broken && placement_valid {
+            let next_index = springs.len().min(group + 1);
+            total += solve(&springs[next_index..], &groups[1..], memo);
+        }
+
+        // If this spring is '?' it could be operational, in which case we should skip it and
+        // consider whether these groups fit into the remaining springs.
+        if current_spring == "?" {
+            total += solve(&springs[1..], groups, memo);
+        }
+
+        // Add this answer to the memo
+        memo.insert((springs.to_string(), groups.to_vec()), total);
+        total
+    }
+
+    const TEST: &str = "
+        ???.### 1,1,3
+        .??..??...?##. 1,1,3
+        ?#?#?#?#?#?#?#? 1,3,1,6
+        ????.#...#... 4,1,1
+        ????.######..#####. 1,6,5
+        ?###???????? 3,2,1
+    ";
+
+    /// Unfolded as in part 2.
+    const FIVE: Unfold = Unfold {
+        times: 5,
+        separator: Spring::Unknown,
+    };
+
+    fn record(line: &str) -> Record {
+        parse(line).unwrap().remove(0)
+    }
 
     #[test]
     fn both_test() {
-        let input = parse(
-            "???.### 1,1,3
-            .??..??...?##. 1,1,3
-            ?#?#?#?#?#?#?#? 1,3,1,6
-            ????.#...#... 4,1,1
-            ????.######..#####. 1,6,5
-            ?###???????? 3,2,1",
-        );
+        let input = parse(TEST).unwrap();
         assert_eq!(part1(&input), 21);
         assert_eq!(part2(&input), 525152);
     }
 
+    #[test_case("???.### 1,1,3", 1, 1)]
+    #[test_case(".??..??...?##. 1,1,3", 4, 16384)]
+    #[test_case("?#?#?#?#?#?#?#? 1,3,1,6", 1, 1)]
+    #[test_case("????.#...#... 4,1,1", 1, 16)]
+    #[test_case("????.######..#####. 1,6,5", 4, 2500)]
+    #[test_case("?###???????? 3,2,1", 10, 506250)]
+    #[test_case("# 1", 1, 1 ; "single")]
+    #[test_case("?.# 2", 0, 0 ; "impossible")]
+    #[test_case("??? 1", 3, 3003 ; "unknown")]
+    fn count_matches_oracle(line: &str, folded: u128, unfolded: u128) {
+        for (unfold, expected) in [(Unfold::FOLDED, folded), (FIVE, unfolded)] {
+            let record = record(line).unfold(unfold);
+            let oracle = solve(&record.springs, &record.groups, &mut HashMap::new());
+            assert_eq!(oracle as u128, expected);
+            assert_eq!(record.count(), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn unfold() {
+        let record = record(".# 1");
+        let unfolded = record.unfold(Unfold {
+            times: 3,
+            separator: Spring::Operational,
+        });
+        assert_eq!(unfolded.springs, ".#..#..#");
+        assert_eq!(unfolded.groups, [1, 1, 1]);
+        assert_eq!(record.unfold(FIVE).springs, ".#?.#?.#?.#?.#");
+        assert_eq!(record.unfold(Unfold::FOLDED), record);
+
+        let empty = record.unfold(Unfold {
+            times: 0,
+            separator: Spring::Unknown,
+        });
+        assert_eq!(empty.count(), Ok(1));
+    }
+
+    #[test_case("???.### 1,1,3", Spring::Unknown, &[1, 1, 1, 1, 1] ; "fixed")]
+    #[test_case(".??..??...?##. 1,1,3", Spring::Unknown, &[4, 32, 256, 2048, 16384] ; "geometric")]
+    #[test_case("?###???????? 3,2,1", Spring::Unknown, &[10, 150, 2250, 33750, 506250] ; "last example")]
+    #[test_case("?###???????? 3,2,1", Spring::Operational, &[10, 100, 1000, 10000, 100000] ; "independent copies")]
+    #[test_case("# 1", Spring::Damaged, &[1, 0, 0, 0, 0] ; "joined groups")]
+    fn growth(line: &str, separator: Spring, expected: &[u128]) {
+        let growth = record(line).growth(separator, expected.len());
+        assert_eq!(growth, expected.iter().map(|&n| Ok(n)).collect::<Vec<_>>());
+    }
+
     #[test]
-    fn mainline() {
-        let input = parse(&parser::load_input_string(12));
-        assert_eq!(part1(&input), 7307);
-        assert_eq!(part2(&input), 3415570893842);
+    fn overflow() {
+        // Each copy holds exactly one group, which can be placed 3 ways, so 3^81 is more than
+        // a u128 holds
+        let record = record("??#??? 3");
+        let growth = record.growth(Spring::Operational, 81);
+        assert_eq!(growth[79], Ok(3u128.pow(80)));
+        assert_eq!(growth[80], Err(Overflow));
+        let unfolded = record.unfold(Unfold {
+            times: 81,
+            separator: Spring::Operational,
+        });
+        assert_eq!(unfolded.arrangement(0), Err(Overflow));
+        assert_eq!(
+            total(std::slice::from_ref(&record), FIVE),
+            Ok(record.unfold(FIVE).count().unwrap())
+        );
+        let records = vec![
+            record.unfold(Unfold {
+                times: 80,
+                separator: Spring::Operational
+            });
+            3
+        ];
+        assert_eq!(total(&records, Unfold::FOLDED), Err(Overflow));
+
+        // A group far longer than the row simply doesn't fit
+        let huge = self::record("??#? 1,18446744073709551615");
+        assert_eq!(huge.count(), Ok(0));
+        assert_eq!(huge.arrangement(0), Ok(None));
+    }
+
+    /// Whether an arrangement fills in the record's unknown springs and holds its groups.
+    fn fits(record: &Record, arrangement: &str) -> bool {
+        arrangement.len() == record.springs.len()
+            && (record.springs.chars().zip(arrangement.chars()))
+                .all(|(p, a)| (p == '?' && a != '?') || p == a)
+            && arrangement
+                .split('.')
+                .filter(|g| !g.is_empty())
+                .map(str::len)
+                .eq(record.groups.iter().copied())
+    }
+
+    #[test]
+    fn arrangements_match_counts() {
+        for record in parse(TEST).unwrap() {
+            let count = record.count().unwrap();
+            let arrangements = record
+                .arrangements(usize::MAX)
+                .unwrap()
+                .collect::<Vec<String>>();
+            assert_eq!(arrangements.len() as u128, count, "{}", record.springs);
+            assert!(arrangements.iter().all(|a| fits(&record, a)));
+            assert!(arrangements.windows(2).all(|w| w[0] < w[1]));
+            assert_eq!(record.arrangement(count), Ok(None));
+        }
+    }
+
+    #[test]
+    fn arrangements_in_order() {
+        let record = record(".??..??...?##. 1,1,3");
+        assert_eq!(
+            record.arrangements(3).unwrap().collect::<Vec<String>>(),
+            [".#...#....###.", ".#....#...###.", "..#..#....###."]
+        );
+        assert_eq!(
+            record.arrangement(3),
+            Ok(Some("..#...#...###.".to_string()))
+        );
+
+        // Unfolded there are far too many to list, but any one can still be found
+        let record = self::record("?###???????? 3,2,1").unfold(FIVE);
+        assert_eq!(record.count(), Ok(506250));
+        let last = record.arrangement(506249).unwrap().unwrap();
+        assert!(fits(&record, &last));
+        assert_eq!(record.arrangements(10).unwrap().count(), 10);
+    }
+
+    #[test]
+    fn sample_uniformly() {
+        let record = record("?###???????? 3,2,1");
+        let mut rng = StdRng::seed_from_u64(12);
+        let mut seen = HashMap::new();
+        for _ in 0..1000 {
+            let arrangement = record.sample(&mut rng).unwrap().unwrap();
+            assert!(fits(&record, &arrangement));
+            *seen.entry(arrangement).or_insert(0) += 1;
+        }
+        // Each of the 10 arrangements should turn up about 100 times
+        assert_eq!(seen.len(), 10);
+        assert!(seen.values().all(|&n| (50..150).contains(&n)), "{:?}", seen);
+
+        let impossible = self::record("?.# 2");
+        assert_eq!(impossible.sample(&mut rng), Ok(None));
+    }
+
+    #[test]
+    #[ignore = "timing comparison, run with --release --ignored --nocapture"]
+    fn benchmark() {
+        for account in crate::answers::accounts() {
+            let input = parse(&account.source.read(12).expect("Unable to read input")).unwrap();
+            let records = input
+                .iter()
+                .map(|r| r.unfold(FIVE))
+                .collect::<Vec<Record>>();
+
+            let timer = Instant::now();
+            let recursive = records
+                .iter()
+                .map(|r| solve(&r.springs, &r.groups, &mut HashMap::new()))
+                .sum::<usize>();
+            let recursive_time = timer.elapsed();
+
+            let timer = Instant::now();
+            let table = records.iter().map(|r| r.count().unwrap()).sum::<u128>();
+            let table_time = timer.elapsed();
+
+            let timer = Instant::now();
+            let parallel = total(&records, Unfold::FOLDED).unwrap();
+            let parallel_time = timer.elapsed();
+
+            println!(
+                "{}: recursive {:?}, table {:?} ({:.1}x), parallel {:?} ({:.1}x)",
+                account.name,
+                recursive_time,
+                table_time,
+                recursive_time.as_secs_f64() / table_time.as_secs_f64(),
+                parallel_time,
+                recursive_time.as_secs_f64() / parallel_time.as_secs_f64()
+            );
+            assert_eq!(table, recursive as u128);
+            assert_eq!(parallel, recursive as u128);
+        }
+    }
+
+    #[test_case("???.### 1,1,3\n\n.??..??...?##. 1,1,3", 2, 1, "", "expected springs" ; "blank line")]
+    #[test_case("???.### 1,1,3\n.??..?x...?##. 1,1,3", 2, 7, "x", "expected ., # or ?" ; "spring")]
+    #[test_case("???.### 1,1,3\n.??..??...?##.", 2, 15, "", "expected groups" ; "missing groups")]
+    #[test_case("???.### 1,a,3", 1, 11, "a", "unable to parse group" ; "group")]
+    #[test_case("???.### 1,0,3", 1, 11, "0", "expected a group of at least one spring" ; "empty group")]
+    fn parse_error(input: &str, line: usize, column: usize, token: &str, reason: &str) {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err, ParseError::new(12, line, column, token, reason));
     }
 }
@@ -1,3 +1,5 @@
+use crate::parser::{self, ParseError};
+
 trait Symmetrical {
     fn is_symmetrical(&self, index: usize) -> bool;
 }
@@ -104,20 +106,23 @@ impl Mirror for Pattern {
     }
 }
 
-fn get_patterns(input: &str) -> Vec<Pattern> {
+fn get_patterns(input: &str) -> Result<Vec<Pattern>, ParseError> {
     let mut patterns = Vec::new();
     let mut pattern = Vec::new();
 
-    for line in input.trim().lines().map(|l| l.trim()) {
-        if line.is_empty() {
+    for line in parser::lines(13, input) {
+        if line.text.is_empty() {
             patterns.push(pattern.clone());
             pattern.clear();
             continue;
         }
-        pattern.push(line.chars().collect::<Vec<_>>());
+        if let Some(pos) = line.text.find(|c| c != '.' && c != '#') {
+            return Err(line.error_at(pos, "expected '.' or '#'"));
+        }
+        pattern.push(line.text.chars().collect::<Vec<_>>());
     }
     patterns.push(pattern);
-    patterns
+    Ok(patterns)
 }
 
 fn inner_transpose<T>(v: &[Vec<T>]) -> Vec<Vec<T>>
@@ -131,7 +136,7 @@ where
 }
 
 #[aoc_generator(day13)]
-pub fn parse(input: &str) -> Vec<Pattern> {
+pub fn parse(input: &str) -> Result<Vec<Pattern>, ParseError> {
     get_patterns(input)
 }
 
@@ -170,7 +175,6 @@ fn part2(patterns: &[Pattern]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser;
     use test_case::test_case;
 
     #[test_case(
@@ -196,15 +200,14 @@ mod tests {
         ;"e1"
     )]
     fn both_test(input: &str, p1: usize, p2: usize) {
-        let patterns = &parse(input);
+        let patterns = &parse(input).unwrap();
         assert_eq!(part1(patterns), p1);
         assert_eq!(part2(patterns), p2);
     }
 
     #[test]
-    fn mainline() {
-        let patterns = &parse(&parser::load_input_string(13));
-        assert_eq!(part1(patterns), 33975);
-        assert_eq!(part2(patterns), 29083);
+    fn parse_error() {
+        let err = parse("#.##..##.\n..#.##.#.\n\n#...##..#\n#...o#..#").unwrap_err();
+        assert_eq!(err, ParseError::new(13, 5, 5, "o", "expected '.' or '#'"));
     }
 }
@@ -1,36 +1,30 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    fmt,
-};
+use std::{collections::VecDeque, fmt};
 
-type Point = (usize, usize);
+use crate::{grid::Grid, parser::ParseError};
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum Rock {
     Empty,
     Round,
     Cube,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 struct Platform {
-    layout: HashMap<Point, Rock>,
-    width: usize,
-    height: usize,
+    layout: Grid<Rock>,
 }
 
 impl Platform {
     fn tilt(mut self) -> Self {
-        for col in 0..self.width {
+        for col in 0..self.layout.width() {
             let mut empty_spaces = VecDeque::new();
 
-            for row in 0..self.height {
-                match self.layout[&(row, col)] {
+            for row in 0..self.layout.height() {
+                match self.layout[(row, col)] {
                     Rock::Empty => empty_spaces.push_back((row, col)),
                     Rock::Round => {
                         if let Some(space) = empty_spaces.pop_front() {
-                            self.layout.insert(space, Rock::Round);
-                            self.layout.insert((row, col), Rock::Empty);
+                            self.layout.swap(space, (row, col));
                             empty_spaces.push_back((row, col));
                         }
                     }
@@ -44,17 +38,7 @@ impl Platform {
     }
 
     fn rotate_clockwise(mut self) -> Self {
-        let mut clockwise = HashMap::new();
-
-        for row in 0..self.height {
-            for col in 0..self.width {
-                clockwise.insert(
-                    (col, self.height - row - 1),
-                    self.layout[&(row, col)].clone(),
-                );
-            }
-        }
-        self.layout = clockwise;
+        self.layout = self.layout.rotate_clockwise();
         self
     }
 
@@ -109,57 +93,40 @@ impl Platform {
     }
 
     fn total_load(&self) -> usize {
-        let mut load = 0;
-        for row in 0..self.height {
-            for col in 0..self.width {
-                if self.layout[&(row, col)] == Rock::Round {
-                    load += self.height - row;
-                }
-            }
-        }
-        load
+        self.layout
+            .iter()
+            .filter(|(_, rock)| **rock == Rock::Round)
+            .map(|((row, _), _)| self.layout.height() - row)
+            .sum()
+    }
+}
+
+impl fmt::Display for Rock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Rock::Empty => '.',
+            Rock::Round => 'O',
+            Rock::Cube => '#',
+        };
+        write!(f, "{}", symbol)
     }
 }
 
 impl fmt::Display for Platform {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let symbol = match self.layout[&(row, col)] {
-                    Rock::Empty => '.',
-                    Rock::Round => 'O',
-                    Rock::Cube => '#',
-                };
-                write!(f, "{}", symbol)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.layout)
     }
 }
 
 #[aoc_generator(day14)]
-fn parse(input: &str) -> Platform {
-    Platform {
-        layout: input
-            .trim()
-            .lines()
-            .enumerate()
-            .flat_map(|(row, line)| {
-                line.trim().chars().enumerate().map(move |(col, c)| {
-                    let rock = match c {
-                        'O' => Rock::Round,
-                        '#' => Rock::Cube,
-                        '.' => Rock::Empty,
-                        _ => panic!("Invalid rock char"),
-                    };
-                    ((row, col), rock)
-                })
-            })
-            .collect(),
-        width: input.trim().lines().last().unwrap().trim().len(),
-        height: input.trim().lines().count(),
-    }
+fn parse(input: &str) -> Result<Platform, ParseError> {
+    let layout = Grid::parse(14, input, |c| match c {
+        'O' => Ok(Rock::Round),
+        '#' => Ok(Rock::Cube),
+        '.' => Ok(Rock::Empty),
+        _ => Err("invalid rock"),
+    })?;
+    Ok(Platform { layout })
 }
 
 #[aoc(day14, part1)]
@@ -174,8 +141,6 @@ fn part2(input: &Platform) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser;
-
     use super::*;
 
     const TEST: &str = "
@@ -193,17 +158,19 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(TEST)), 136);
+        assert_eq!(part1(&parse(TEST).unwrap()), 136);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(TEST)), 64);
+        assert_eq!(part2(&parse(TEST).unwrap()), 64);
     }
 
     #[test]
-    fn mainline() {
-        assert_eq!(part1(&parse(&parser::load_input_string(14))), 110128);
-        // assert_eq!(part2(&parse(&parser::load_input_string(14))), 103861);
+    fn parse_error() {
+        let err = parse("O....#....\nO.OO#...x#").unwrap_err();
+        assert_eq!(err, ParseError::new(14, 2, 9, "x", "invalid rock"));
+        let err = parse("O....#....\nO.OO#....").unwrap_err();
+        assert_eq!(err.reason, "expected all rows to be the same width");
     }
 }
@@ -1,9 +1,12 @@
+use crate::parser::{self, Line, ParseError};
+
 #[derive(Debug, Clone)]
 struct Lens {
     label: String,
     focal_len: usize,
 }
 
+#[derive(Debug)]
 enum Operation {
     Insert(usize),
     Remove,
@@ -15,6 +18,7 @@ fn hash(label: &str) -> usize {
         .fold(0, |acc, c| (acc + c as usize) * 17 % 256)
 }
 
+#[derive(Debug)]
 struct Parser {
     steps: Vec<String>,
     init_seq: Vec<(String, Operation)>,
@@ -73,18 +77,17 @@ impl LensBox for Vec<Vec<Lens>> {
     }
 }
 
-fn initializer(step: &str) -> Option<(String, Operation)> {
+fn initializer<'a>(
+    line: &Line<'a>,
+    step: &'a str,
+) -> Option<Result<(String, Operation), ParseError>> {
     if let Some(label) = step.strip_suffix('-') {
-        Some((label.to_string(), Operation::Remove))
+        Some(Ok((label.to_string(), Operation::Remove)))
     } else if let Some((label, focal_len)) = step.split_once('=') {
-        Some((
-            label.to_string(),
-            Operation::Insert(
-                focal_len
-                    .parse::<usize>()
-                    .expect("Unable to parse focal length"),
-            ),
-        ))
+        Some(
+            line.parse::<usize>(focal_len, "unable to parse focal length")
+                .map(|focal_len| (label.to_string(), Operation::Insert(focal_len))),
+        )
     } else {
         // Steps which are valid for Part 1 may not be valid for Part 2.
         // Return None so they can be discarded for Part 2.
@@ -93,11 +96,20 @@ fn initializer(step: &str) -> Option<(String, Operation)> {
 }
 
 #[aoc_generator(day15)]
-fn parse(input: &str) -> Parser {
-    Parser {
-        steps: input.trim().split(',').map(|s| s.to_string()).collect(),
-        init_seq: input.trim().split(',').filter_map(initializer).collect(),
+fn parse(input: &str) -> Result<Parser, ParseError> {
+    let mut res = Parser {
+        steps: Vec::new(),
+        init_seq: Vec::new(),
+    };
+    for line in parser::lines(15, input) {
+        for step in line.text.split(',') {
+            res.steps.push(step.to_string());
+            if let Some(init) = initializer(&line, step) {
+                res.init_seq.push(init?);
+            }
+        }
     }
+    Ok(res)
 }
 
 #[aoc(day15, part1)]
@@ -112,8 +124,6 @@ fn part2(input: &Parser) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser;
-
     use super::*;
     use test_case::test_case;
 
@@ -135,7 +145,7 @@ mod tests {
         ;"e3"
     )]
     fn part1_example(input: &str, want: usize) {
-        assert_eq!(part1(&parse(input)), want);
+        assert_eq!(part1(&parse(input).unwrap()), want);
     }
 
     #[test_case(
@@ -144,13 +154,15 @@ mod tests {
         ;"e1"
     )]
     fn part2_example(input: &str, want: usize) {
-        assert_eq!(part2(&parse(input)), want);
+        assert_eq!(part2(&parse(input).unwrap()), want);
     }
 
     #[test]
-    fn mainline() {
-        let input = &parser::load_input_string(15);
-        assert_eq!(part1(&parse(input)), 522547);
-        assert_eq!(part2(&parse(input)), 229271);
+    fn parse_error() {
+        let err = parse("rn=1,cm-,qp=x,cm=2").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(15, 1, 13, "x", "unable to parse focal length")
+        );
     }
 }
@@ -1,30 +1,12 @@
-use std::{
-    cmp::max,
-    collections::{HashMap, HashSet},
-};
-
-type Point = (i32, i32);
-
-#[derive(Clone, Eq, Hash, PartialEq)]
-enum Heading {
-    North,
-    East,
-    South,
-    West,
-}
+use std::{cmp::max, collections::HashSet};
 
-impl Heading {
-    pub fn from(&self, loc: Point) -> Point {
-        match self {
-            Heading::North => (loc.0 - 1, loc.1),
-            Heading::East => (loc.0, loc.1 + 1),
-            Heading::South => (loc.0 + 1, loc.1),
-            Heading::West => (loc.0, loc.1 - 1),
-        }
-    }
-}
+use crate::{
+    direction::Direction,
+    grid::{Grid, Point},
+    parser::ParseError,
+};
 
-#[derive(PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 enum Route {
     NorthAndSouth,
     EastAndWest,
@@ -33,72 +15,72 @@ enum Route {
     Empty,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 struct Tile {
     route: Route,
-    beams: HashSet<Heading>,
+    beams: HashSet<Direction>,
 }
 
 impl Tile {
-    pub fn new(symbol: char) -> Self {
+    pub fn new(symbol: char) -> Option<Self> {
         let route = match symbol {
             '|' => Route::NorthAndSouth,
             '-' => Route::EastAndWest,
             '\\' => Route::Backslash,
             '/' => Route::Forwardslash,
             '.' => Route::Empty,
-            _ => panic!("Unrecognised tile route: {}", symbol),
+            _ => return None,
         };
-        Self {
+        Some(Self {
             route,
             beams: HashSet::new(),
-        }
+        })
     }
 
-    pub fn forward_beam(&mut self, heading: &Heading) -> bool {
-        self.beams.insert(heading.clone())
+    pub fn forward_beam(&mut self, heading: Direction) -> bool {
+        self.beams.insert(heading)
     }
 
     pub fn is_energized(&self) -> bool {
         !self.beams.is_empty()
     }
 
-    pub fn get_heading(&self, heading: Heading) -> Vec<Heading> {
+    pub fn get_heading(&self, heading: Direction) -> Vec<Direction> {
         match heading {
-            Heading::North => match self.route {
+            Direction::North => match self.route {
                 Route::NorthAndSouth | Route::Empty => vec![heading],
-                Route::EastAndWest => vec![Heading::East, Heading::West],
-                Route::Backslash => vec![Heading::West],
-                Route::Forwardslash => vec![Heading::East],
+                Route::EastAndWest => vec![Direction::East, Direction::West],
+                Route::Backslash => vec![Direction::West],
+                Route::Forwardslash => vec![Direction::East],
             },
-            Heading::East => match self.route {
-                Route::NorthAndSouth => vec![Heading::North, Heading::South],
+            Direction::East => match self.route {
+                Route::NorthAndSouth => vec![Direction::North, Direction::South],
                 Route::EastAndWest | Route::Empty => vec![heading],
-                Route::Backslash => vec![Heading::South],
-                Route::Forwardslash => vec![Heading::North],
+                Route::Backslash => vec![Direction::South],
+                Route::Forwardslash => vec![Direction::North],
             },
-            Heading::South => match self.route {
+            Direction::South => match self.route {
                 Route::NorthAndSouth | Route::Empty => vec![heading],
-                Route::EastAndWest => vec![Heading::East, Heading::West],
-                Route::Backslash => vec![Heading::East],
-                Route::Forwardslash => vec![Heading::West],
+                Route::EastAndWest => vec![Direction::East, Direction::West],
+                Route::Backslash => vec![Direction::East],
+                Route::Forwardslash => vec![Direction::West],
             },
-            Heading::West => match self.route {
-                Route::NorthAndSouth => vec![Heading::North, Heading::South],
+            Direction::West => match self.route {
+                Route::NorthAndSouth => vec![Direction::North, Direction::South],
                 Route::EastAndWest | Route::Empty => vec![heading],
-                Route::Backslash => vec![Heading::North],
-                Route::Forwardslash => vec![Heading::South],
+                Route::Backslash => vec![Direction::North],
+                Route::Forwardslash => vec![Direction::South],
             },
         }
     }
 }
 
+#[derive(Debug)]
 struct Parser {
     layout: Layout,
-    max: Point,
 }
 
-type Layout = HashMap<(i32, i32), Tile>;
+type Layout = Grid<Tile>;
 
 trait Energized {
     fn energized_count(&self) -> usize;
@@ -111,27 +93,18 @@ impl Energized for Layout {
 }
 
 #[aoc_generator(day16)]
-fn parse(input: &str) -> Parser {
-    let mut layout = Layout::new();
-
-    for (row, line) in input.trim().lines().enumerate() {
-        for (col, c) in line.trim().chars().enumerate() {
-            let tile = Tile::new(c);
-            layout.insert((row as i32, col as i32), tile);
-        }
-    }
-
-    let max = layout.keys().cloned().max().unwrap_or((0, 0));
-
-    Parser { layout, max }
+fn parse(input: &str) -> Result<Parser, ParseError> {
+    let layout = Grid::parse(16, input, |c| Tile::new(c).ok_or("unrecognised tile route"))?;
+    Ok(Parser { layout })
 }
 
-fn dfs(layout: &mut Layout, point: Point, heading: Heading) {
-    if let Some(tile) = layout.get_mut(&point) {
-        if tile.forward_beam(&heading) {
+fn dfs(layout: &mut Layout, point: Point, heading: Direction) {
+    if let Some(tile) = layout.get_mut(point) {
+        if tile.forward_beam(heading) {
             for h in tile.get_heading(heading) {
-                let next_point = h.from(point);
-                dfs(layout, next_point, h);
+                if let Some(next_point) = h.step(point, 1) {
+                    dfs(layout, next_point, h);
+                }
             }
         }
     }
@@ -140,23 +113,24 @@ fn dfs(layout: &mut Layout, point: Point, heading: Heading) {
 #[aoc(day16, part1)]
 fn part1(input: &Parser) -> usize {
     let mut layout = input.layout.clone();
-    dfs(&mut layout, (0, 0), Heading::East);
+    dfs(&mut layout, (0, 0), Direction::East);
     layout.energized_count()
 }
 
 #[aoc(day16, part2)]
 fn part2(input: &Parser) -> usize {
-    let candidates: Vec<(Point, Heading)> = (0..input.max.1)
+    let (last_row, last_col) = input.layout.last();
+    let candidates: Vec<(Point, Direction)> = (0..=last_col)
         .flat_map(|col| {
             vec![
-                ((0, col), Heading::South),
-                ((input.max.0, col), Heading::North),
+                ((0, col), Direction::South),
+                ((last_row, col), Direction::North),
             ]
         })
-        .chain((0..input.max.0).flat_map(|row| {
+        .chain((0..=last_row).flat_map(|row| {
             vec![
-                ((row, 0), Heading::East),
-                ((row, input.max.1), Heading::West),
+                ((row, 0), Direction::East),
+                ((row, last_col), Direction::West),
             ]
         }))
         .collect();
@@ -165,7 +139,7 @@ fn part2(input: &Parser) -> usize {
 
     candidates.iter().for_each(|(point, heading)| {
         let mut layout = input.layout.clone();
-        dfs(&mut layout, *point, heading.clone());
+        dfs(&mut layout, *point, *heading);
         result = max(result, layout.energized_count());
     });
 
@@ -174,8 +148,6 @@ fn part2(input: &Parser) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser;
-
     use super::*;
 
     const TEST: &str = r"
@@ -193,18 +165,20 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(TEST)), 46);
+        assert_eq!(part1(&parse(TEST).unwrap()), 46);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(TEST)), 51);
+        assert_eq!(part2(&parse(TEST).unwrap()), 51);
     }
 
     #[test]
-    fn mainline() {
-        let input = &parser::load_input_string(16);
-        assert_eq!(part1(&parse(input)), 6740);
-        assert_eq!(part2(&parse(input)), 7041);
+    fn parse_error() {
+        let err = parse(".|...\\....\n|.-.\\..+..").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(16, 2, 8, "+", "unrecognised tile route")
+        );
     }
 }
@@ -1,351 +1,321 @@
 use std::{
-    collections::{BinaryHeap, HashMap, HashSet},
-    hash::Hash,
+    error::Error,
+    fmt::Write,
+    fs, io,
+    path::{self, PathBuf},
+    time::Instant,
 };
 
-type Point = (usize, usize);
+use crate::{
+    direction::Direction,
+    grid::{Grid, Point},
+    parser::ParseError,
+    search::{self, Path, Stats},
+};
 
-#[derive(PartialEq, Eq, Clone, Copy, Hash)]
-enum Direction {
-    Start,
-    North,
-    East,
-    South,
-    West,
-}
+/// Size in pixels of each block in the SVG
+const SVG_SCALE: usize = 12;
 
-trait Flippable {
-    fn flip(&self) -> Direction;
+/// A crucible's position, the direction it entered from and how many blocks it has moved
+/// in that direction. The start has no direction as it hasn't been entered from anywhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Crucible {
+    pub pos: Point,
+    pub dir: Option<Direction>,
+    pub steps: usize,
 }
 
-impl Flippable for Direction {
-    fn flip(&self) -> Direction {
-        match self {
-            Direction::Start => Direction::Start,
-            Direction::North => Direction::South,
-            Direction::East => Direction::West,
-            Direction::South => Direction::North,
-            Direction::West => Direction::East,
-        }
-    }
+/// The route losing the least heat, as each state of the crucible from the start to the
+/// destination inclusive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Route {
+    pub heat_loss: usize,
+    pub crucibles: Vec<Crucible>,
 }
 
-#[derive(Eq, PartialEq, Clone, Copy)]
-struct Node {
-    pos: Point,
-    f: usize,
-    g: usize,
-    dir: Direction,
-    steps: usize,
-    parent: NodeKey,
-}
-
-impl Node {
-    fn from(
-        pos: Point,
-        dest: Point,
-        g: usize,
-        dir: Direction,
-        steps: usize,
-        parent: NodeKey,
-    ) -> Node {
-        let h = heuristic(pos, dest);
-        Node {
-            pos,
-            f: g + h,
-            g,
-            dir,
-            steps,
-            parent,
+impl From<Path<Crucible, usize>> for Route {
+    fn from(path: Path<Crucible, usize>) -> Self {
+        Self {
+            heat_loss: path.cost,
+            crucibles: path.states,
         }
     }
 }
 
-#[derive(Eq, PartialEq, Clone, Copy, Hash)]
-struct NodeKey {
-    pos: Point,
-    dir: Direction,
-    steps: usize,
+/// Ways of estimating the heat still to be lost on the way to the destination. None of them
+/// overestimate, so they all find the same answer but explore different amounts of the city.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heuristic {
+    /// No estimate, which makes the search Dijkstra's algorithm
+    Zero,
+    /// Taxicab distance to the destination
+    Taxicab,
+    /// Taxicab distance scaled by the least heat lost entering any block
+    ScaledTaxicab,
+    /// Least heat lost reaching the destination from each block if the crucible could turn
+    /// freely, precomputed by searching outwards from the destination
+    ReverseDijkstra,
 }
 
-impl NodeKey {
-    fn from(pos: Point, dir: Direction, steps: usize) -> NodeKey {
-        NodeKey { pos, dir, steps }
-    }
-}
-
-trait OpenList {
-    fn initialise(city: &City, start: Point, dest: Point) -> Self;
-}
-
-impl OpenList for BinaryHeap<Node> {
-    fn initialise(city: &City, start: Point, dest: Point) -> Self {
-        let mut open = BinaryHeap::new();
-
-        for (pos, dir) in city.get_neighbours(start) {
-            let g = city.map.get(&pos).expect("Expected point in city map");
-            open.push(Node::from(
-                pos,
-                dest,
-                *g,
-                dir,
-                1,
-                NodeKey::from(start, Direction::Start, 0),
-            ));
-        }
-
-        open
-    }
+impl Heuristic {
+    pub const ALL: [Heuristic; 4] = [
+        Heuristic::Zero,
+        Heuristic::Taxicab,
+        Heuristic::ScaledTaxicab,
+        Heuristic::ReverseDijkstra,
+    ];
 }
 
-trait ClosedList {
-    fn initialise(city: &City, start: Point, dest: Point) -> Self;
-    #[allow(dead_code)]
-    fn get_path(&self, last_node: Node) -> Vec<Node>;
-    #[allow(dead_code)]
-    fn get_visited(&self) -> HashSet<Point>;
+#[derive(Debug)]
+pub struct City {
+    map: Grid<usize>,
 }
 
-impl ClosedList for HashMap<NodeKey, Node> {
-    fn initialise(city: &City, start: Point, dest: Point) -> Self {
-        let mut closed = HashMap::new();
-        for (_, dir) in city.get_neighbours(start) {
-            closed.insert(
-                NodeKey::from(start, dir, 0),
-                Node::from(
-                    start,
-                    dest,
-                    0,
-                    dir,
-                    0,
-                    NodeKey::from(start, Direction::Start, 0),
-                ),
-            );
-        }
-        closed
+impl City {
+    /// The route from the top left to the bottom right with the least heat loss, for a
+    /// crucible which must move `min_steps` in a direction before turning or stopping and
+    /// can move at most `max_steps` before turning. The time reported includes precomputing
+    /// the heuristic.
+    pub fn least_heat_loss(
+        &self,
+        min_steps: usize,
+        max_steps: usize,
+        heuristic: Heuristic,
+    ) -> (Option<Route>, Stats) {
+        let timer = Instant::now();
+        let start = Crucible {
+            pos: (0, 0),
+            dir: None,
+            steps: 0,
+        };
+        let dest = self.map.last();
+        let estimates = self.estimates(heuristic);
+
+        let (path, mut stats) = search::a_star_with_stats(
+            start,
+            |crucible| self.moves(crucible, min_steps, max_steps),
+            |crucible| crucible.pos == dest && crucible.steps >= min_steps,
+            |crucible| estimates[crucible.pos],
+        );
+        stats.elapsed = timer.elapsed();
+        (path.map(Route::from), stats)
     }
 
-    fn get_path(&self, last_node: Node) -> Vec<Node> {
-        let mut path = Vec::new();
-        let mut curr_node = last_node;
-        while curr_node.parent.dir != Direction::Start {
-            path.push(curr_node);
-            curr_node = *self
-                .get(&curr_node.parent)
-                .expect("Node parent does not exist.");
+    /// The heuristic's estimate of the heat still to be lost from each block.
+    fn estimates(&self, heuristic: Heuristic) -> Grid<usize> {
+        let dest = self.map.last();
+        let (width, height) = (self.map.width(), self.map.height());
+        match heuristic {
+            Heuristic::Zero => Grid::filled(width, height, 0),
+            Heuristic::Taxicab => {
+                let cells = self.map.positions().map(|pos| taxicab(pos, dest)).collect();
+                Grid::new(width, height, cells)
+            }
+            Heuristic::ScaledTaxicab => {
+                let least = self.map.iter().map(|(_, &heat_loss)| heat_loss).min();
+                let least = least.unwrap_or(0);
+                let cells = self
+                    .map
+                    .positions()
+                    .map(|pos| taxicab(pos, dest) * least)
+                    .collect();
+                Grid::new(width, height, cells)
+            }
+            Heuristic::ReverseDijkstra => {
+                // Stepping back from a block to its neighbour undoes the heat lost entering it
+                let costs = search::costs(dest, |&pos| {
+                    self.map
+                        .neighbours4(pos)
+                        .map(move |prev| (prev, self.map[pos]))
+                });
+                let cells = self.map.positions().map(|pos| costs[&pos]).collect();
+                Grid::new(width, height, cells)
+            }
         }
-        path.push(curr_node);
-        path
     }
 
-    fn get_visited(&self) -> HashSet<Point> {
-        self.keys().map(|k| k.pos).collect()
-    }
-}
-
-struct City {
-    map: HashMap<Point, usize>,
-    max: Point,
-}
-
-impl City {
-    fn a_star_search(
+    /// Every block a crucible can move to next, along with the heat lost entering it.
+    fn moves(
         &self,
-        start: Point,
-        dest: Point,
-        max_steps: usize,
+        crucible: &Crucible,
         min_steps: usize,
-    ) -> Option<usize> {
-        // OPEN list is a min-heap with lowest f at the top
-        let mut open = BinaryHeap::initialise(self, start, dest);
-
-        // CLOSED list holds the visited nodes
-        let mut closed = HashMap::initialise(self, start, dest);
-
-        while let Some(node) = open.pop() {
-            // Destination reached
-            if node.pos == dest && node.steps >= min_steps {
-                // self.print_path(closed.get_path(node));
-                // self.print_visited(closed.get_visited());
-                return Some(node.g);
-            }
-
-            let node_key = NodeKey::from(node.pos, node.dir, node.steps);
-
-            // Skip if we've already visited this node
-            if closed.contains_key(&node_key) {
-                continue;
-            }
-
-            for (pos, dir) in self.get_neighbours(node.pos) {
+        max_steps: usize,
+    ) -> impl Iterator<Item = (Crucible, usize)> + '_ {
+        let crucible = *crucible;
+        Direction::ALL.into_iter().filter_map(move |dir| {
+            let steps = match crucible.dir {
+                None => 1,
                 // Skip a neighbour which would be going back on ourselves
-                if dir == node.dir.flip() {
-                    continue;
-                }
-
+                Some(current) if dir == current.reverse() => return None,
+                Some(current) if dir == current => crucible.steps + 1,
                 // Skip changes in direction if min steps have not been taken
-                if dir != node.dir && node.steps < min_steps {
-                    continue;
-                }
-
-                let steps = if dir == node.dir { node.steps + 1 } else { 1 };
-
-                // Skip neighbours which exceed the single direction limit
-                if steps > max_steps {
-                    continue;
-                }
-
-                // If neighbour exists in CLOSED list with <= g, skip
-                if closed
-                    .get(&NodeKey::from(pos, dir, steps))
-                    .is_some_and(|&n| n.g <= node.g)
-                {
-                    continue;
-                }
-
-                let heat_loss = node.g + self.map.get(&pos).expect("Expected point in city map");
-
-                // Add neighbour to OPEN list
-                open.push(Node::from(pos, dest, heat_loss, dir, steps, node_key));
+                Some(_) if crucible.steps < min_steps => return None,
+                Some(_) => 1,
+            };
 
-                // Add node to CLOSED list
-                closed.insert(node_key, node);
+            // Skip neighbours which exceed the single direction limit
+            if steps > max_steps {
+                return None;
             }
-        }
 
-        None
-    }
-
-    fn get_neighbours(&self, pos: Point) -> Vec<(Point, Direction)> {
-        let mut neighbours = Vec::new();
-
-        if pos.0 > 0 {
-            neighbours.push(((pos.0 - 1, pos.1), Direction::West));
-        }
-        if pos.0 < self.max.0 {
-            neighbours.push(((pos.0 + 1, pos.1), Direction::East));
-        }
-        if pos.1 > 0 {
-            neighbours.push(((pos.0, pos.1 - 1), Direction::North));
-        }
-        if pos.1 < self.max.1 {
-            neighbours.push(((pos.0, pos.1 + 1), Direction::South));
-        }
-
-        neighbours
+            let pos = dir.step(crucible.pos, 1)?;
+            let heat_loss = *self.map.get(pos)?;
+            let next = Crucible {
+                pos,
+                dir: Some(dir),
+                steps,
+            };
+            Some((next, heat_loss))
+        })
     }
 
-    #[allow(dead_code)]
-    fn print_path(&self, path: Vec<Node>) {
-        let mut grid: HashMap<Point, String> = self.get_grid();
-
-        for node in path {
-            let symbol = match node.dir {
-                Direction::Start => "#",
-                Direction::North => "^",
-                Direction::East => ">",
-                Direction::South => "v",
-                Direction::West => "<",
+    /// The heat loss of each block, with the direction the route enters it by drawn over
+    /// the blocks it passes through.
+    pub fn ascii(&self, route: &Route) -> String {
+        let mut grid = self
+            .map
+            .map(|heat_loss| char::from_digit(*heat_loss as u32, 10).unwrap_or('#'));
+
+        for crucible in &route.crucibles {
+            let symbol = match crucible.dir {
+                Some(Direction::North) => '^',
+                Some(Direction::East) => '>',
+                Some(Direction::South) => 'v',
+                Some(Direction::West) => '<',
+                None => continue,
             };
-            grid.insert(node.pos, symbol.to_string());
+            grid[crucible.pos] = symbol;
         }
 
-        self.print_grid(grid);
+        grid.to_string()
     }
 
-    #[allow(dead_code)]
-    fn print_visited(&self, visited: HashSet<Point>) {
-        let mut grid: HashMap<Point, String> = self.get_grid();
+    /// A heat-map of the city, hotter blocks in deeper red, with the route drawn over it and
+    /// a dot wherever the crucible turns.
+    pub fn svg(&self, route: &Route) -> String {
+        let centre = |(row, col): Point| {
+            (
+                col * SVG_SCALE + SVG_SCALE / 2,
+                row * SVG_SCALE + SVG_SCALE / 2,
+            )
+        };
+        let (width, height) = (self.map.width() * SVG_SCALE, self.map.height() * SVG_SCALE);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            width, height
+        );
+
+        for ((row, col), &heat_loss) in self.map.iter() {
+            // Yellow for 1 through to dark red for 9
+            let step = heat_loss.clamp(1, 9) - 1;
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"hsl({}, 100%, {}%)\"/>",
+                col * SVG_SCALE,
+                row * SVG_SCALE,
+                60 - step * 15 / 2,
+                80 - step * 5,
+                s = SVG_SCALE
+            );
+        }
 
-        for point in visited {
-            grid.insert(point, "#".to_string());
+        let points = route
+            .crucibles
+            .iter()
+            .map(|crucible| {
+                let (x, y) = centre(crucible.pos);
+                format!("{},{}", x, y)
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"#1f4eff\" stroke-width=\"{}\" stroke-linejoin=\"round\"/>",
+            points.join(" "),
+            SVG_SCALE / 4
+        );
+
+        for pair in route.crucibles.windows(2) {
+            if pair[0].dir.is_some() && pair[0].dir != pair[1].dir {
+                let (x, y) = centre(pair[0].pos);
+                let _ = writeln!(
+                    svg,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#1f4eff\"/>",
+                    x,
+                    y,
+                    SVG_SCALE / 3
+                );
+            }
         }
 
-        self.print_grid(grid);
+        svg.push_str("</svg>\n");
+        svg
     }
 
-    fn get_grid(&self) -> HashMap<Point, String> {
-        self.map
-            .iter()
-            .map(|(&point, &_val)| (point, ".".to_string()))
-            .collect()
+    pub fn write_svg(&self, route: &Route, path: impl AsRef<path::Path>) -> io::Result<()> {
+        fs::write(path, self.svg(route))
     }
 
-    fn print_grid(&self, grid: HashMap<Point, String>) {
-        for row in 0..=self.max.1 {
-            let line: String = (0..=self.max.0)
-                .map(|col| {
-                    grid.get(&(col, row))
-                        .expect("Expected to find block in grid")
-                })
-                .cloned()
-                .collect();
-            println!("{}", line);
-        }
-        println!();
+    /// The route a part's crucible takes, `None` for parts the puzzle doesn't have.
+    pub fn route(&self, part: usize) -> Option<Route> {
+        let (min_steps, max_steps) = match part {
+            1 => (0, 3),
+            2 => (4, 10),
+            _ => return None,
+        };
+        self.least_heat_loss(min_steps, max_steps, Heuristic::Taxicab)
+            .0
     }
 }
 
-fn heuristic(pos: Point, dest: Point) -> usize {
-    // Taxicab distance
-    let dx = (pos.0 as isize - dest.0 as isize).unsigned_abs();
-    let dy = (pos.1 as isize - dest.1 as isize).unsigned_abs();
-    dx + dy
-}
-
-impl Ord for Node {
-    // min-heap based on f
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.f.cmp(&other.f).reverse()
+/// Draw each part's route through the city in `input` as `day17-part<N>.svg` in `dir`,
+/// returning the files written.
+pub fn write_routes(
+    input: &str,
+    dir: impl AsRef<path::Path>,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let city = parse(input)?;
+    let mut written = Vec::new();
+    for part in [1, 2] {
+        let route = city.route(part).ok_or("Crucible never reaches the end")?;
+        let path = dir.as_ref().join(format!("day17-part{}.svg", part));
+        city.write_svg(&route, &path)
+            .map_err(|e| format!("Unable to write {}: {}", path.display(), e))?;
+        written.push(path);
     }
+    Ok(written)
 }
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+fn taxicab(pos: Point, dest: Point) -> usize {
+    pos.0.abs_diff(dest.0) + pos.1.abs_diff(dest.1)
 }
 
 #[aoc_generator(day17)]
-fn parse(input: &str) -> City {
-    // hashmap of coords and heat-loss
-    let map: HashMap<(usize, usize), usize> = input
-        .trim()
-        .lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.trim().chars().enumerate().map(move |(col, c)| {
-                (
-                    (col, row),
-                    c.to_string()
-                        .parse::<usize>()
-                        .expect("Error parsing block heat loss"),
-                )
-            })
-        })
-        .collect();
-
-    let max = map.keys().cloned().max().unwrap_or((0, 0));
-
-    City { map, max }
+pub fn parse(input: &str) -> Result<City, ParseError> {
+    // grid of heat-loss
+    let map = Grid::parse(17, input, |c| {
+        c.to_digit(10)
+            .map(|heat_loss| heat_loss as usize)
+            .ok_or("error parsing block heat loss")
+    })?;
+
+    Ok(City { map })
 }
 
 #[aoc(day17, part1)]
 fn part1(city: &City) -> usize {
-    city.a_star_search((0, 0), city.max, 3, 0)
-        .expect("Expected to reach the end!")
+    city.route(1).expect("Expected to reach the end!").heat_loss
 }
 
 #[aoc(day17, part2)]
 fn part2(city: &City) -> usize {
-    city.a_star_search((0, 0), city.max, 10, 4)
-        .expect("Expected to reach the end!")
+    city.route(2).expect("Expected to reach the end!").heat_loss
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::parser;
+    use std::{env, thread};
     use test_case::test_case;
 
     const TEST_A: &str = "
@@ -374,19 +344,138 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(TEST_A)), 102);
+        assert_eq!(part1(&parse(TEST_A).unwrap()), 102);
     }
 
     #[test_case(TEST_A, 94;"A")]
     #[test_case(TEST_B, 71;"B")]
     fn part2_example(input: &str, answer: usize) {
-        assert_eq!(part2(&parse(input)), answer);
+        assert_eq!(part2(&parse(input).unwrap()), answer);
+    }
+
+    #[test]
+    fn render_ascii() {
+        let city = parse(TEST_A).unwrap();
+        let route = city.least_heat_loss(0, 3, Heuristic::Taxicab).0.unwrap();
+        let expected = "
+            2>>34^>>>1323
+            32v>>>35v>623
+            325524565v>54
+            3446585845v52
+            4546657867v>6
+            14385987984v4
+            44578769877v6
+            36378779796v>
+            465496798688v
+            456467998645v
+            12246868655<v
+            25465488877v5
+            43226746555v>
+        ";
+        assert_eq!(
+            city.ascii(&route),
+            parser::test_input(expected).join("\n") + "\n"
+        );
+    }
+
+    #[test]
+    fn render_svg() {
+        let city = parse(TEST_B).unwrap();
+        let route = city.least_heat_loss(4, 10, Heuristic::Taxicab).0.unwrap();
+        assert_eq!(route.heat_loss, 71);
+        assert_eq!(route.crucibles.first().map(|c| c.pos), Some((0, 0)));
+        assert_eq!(route.crucibles.last().map(|c| c.pos), Some((4, 11)));
+
+        let svg = city.svg(&route);
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect ").count(), 12 * 5);
+        assert_eq!(svg.matches("<polyline ").count(), 1);
+        // East along the top for 7, south for 4 and east again for 4
+        assert_eq!(svg.matches("<circle ").count(), 2);
+
+        let path = env::temp_dir().join(format!("day17-{}.svg", std::process::id()));
+        city.write_svg(&route, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), svg);
+        fs::remove_file(path).unwrap();
+
+        let dir = env::temp_dir().join(format!("day17-routes-{}", std::process::id()));
+        fs::create_dir(&dir).unwrap();
+        let written = write_routes(TEST_B, &dir).unwrap();
+        assert_eq!(
+            written,
+            [dir.join("day17-part1.svg"), dir.join("day17-part2.svg")]
+        );
+        assert_eq!(fs::read_to_string(&written[1]).unwrap(), svg);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(write_routes(TEST_B, &dir).is_err());
+    }
+
+    #[test]
+    fn heuristics_agree_on_example() {
+        let city = parse(TEST_A).unwrap();
+        for heuristic in Heuristic::ALL {
+            let cost = |min, max| {
+                city.least_heat_loss(min, max, heuristic)
+                    .0
+                    .unwrap()
+                    .heat_loss
+            };
+            assert_eq!((cost(0, 3), cost(4, 10)), (102, 94), "{:?}", heuristic);
+        }
+    }
+
+    /// Run every heuristic against each account's input for the given parts, reporting how
+    /// much of the city each one explored. Use `--nocapture` to see the report.
+    fn heuristics_agree_on_answers(parts: &[(usize, usize, usize)]) {
+        for account in crate::answers::accounts() {
+            let input = account.source.read(17).expect("Unable to read input");
+            let city = parse(&input).unwrap();
+            for &(part, min, max) in parts {
+                let Some(expected) = account.expected(17, part) else {
+                    continue;
+                };
+                let results = thread::scope(|scope| {
+                    let handles = Heuristic::ALL.map(|heuristic| {
+                        let city = &city;
+                        scope.spawn(move || (heuristic, city.least_heat_loss(min, max, heuristic)))
+                    });
+                    handles.map(|handle| handle.join().expect("Search panicked"))
+                });
+                for (heuristic, (path, stats)) in results {
+                    println!(
+                        "{} part {} {:?}: expanded {}, pushed {}, max open {}, {:?}",
+                        account.name,
+                        part,
+                        heuristic,
+                        stats.expanded,
+                        stats.pushed,
+                        stats.max_open,
+                        stats.elapsed
+                    );
+                    let cost = path.map(|route| route.heat_loss.to_string());
+                    assert_eq!(cost.as_deref(), Some(expected), "{:?}", heuristic);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn heuristics_agree_on_part1() {
+        heuristics_agree_on_answers(&[(1, 0, 3)]);
+    }
+
+    #[test]
+    #[ignore = "slow on full inputs, run with --release --ignored --nocapture"]
+    fn heuristics_agree_on_both_parts() {
+        heuristics_agree_on_answers(&[(1, 0, 3), (2, 4, 10)]);
     }
 
     #[test]
-    fn mainline() {
-        let input = &parse(&parser::load_input_string(17));
-        assert_eq!(part1(input), 1138);
-        assert_eq!(part2(input), 1312);
+    fn parse_error() {
+        let err = parse("2413432311323\n32154a3535623").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(17, 2, 6, "a", "error parsing block heat loss")
+        );
     }
 }
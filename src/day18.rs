@@ -1,24 +1,10 @@
-use core::panic;
-
-#[derive(Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    pub fn from(&self, loc: Point, dist: isize) -> Point {
-        match self {
-            Direction::Up => (loc.0 - dist, loc.1),
-            Direction::Right => (loc.0, loc.1 + dist),
-            Direction::Down => (loc.0 + dist, loc.1),
-            Direction::Left => (loc.0, loc.1 - dist),
-        }
-    }
-}
+use crate::{
+    direction::Direction,
+    geometry::Polygon,
+    parser::{self, ParseError},
+};
 
+#[derive(Debug)]
 struct Instruction {
     direction: Direction,
     distance: isize,
@@ -26,25 +12,6 @@ struct Instruction {
     hex_distance: isize,
 }
 
-type Point = (isize, isize);
-
-trait Perimeter {
-    fn area(&self) -> usize;
-}
-
-impl Perimeter for Vec<(isize, isize)> {
-    /// Calculate area with Shoelace Formula (https://en.wikipedia.org/wiki/Shoelace_formula).
-    /// The starting point must be present at the start and the end of the outline
-    fn area(&self) -> usize {
-        let sum = self.windows(2).fold(0, |acc, matrix| {
-            acc + (matrix[0].0 * matrix[1].1) - (matrix[1].0 * matrix[0].1)
-        });
-        // If the points are labeled sequentially in the counterclockwise direction, then the area is positive,
-        // if they are labeled in the clockwise direction, the area will be negative.
-        (sum.abs() / 2) as usize
-    }
-}
-
 trait Lagoon {
     fn volume(&self, decode: bool) -> usize;
 }
@@ -53,58 +20,67 @@ impl Lagoon for Vec<Instruction> {
     fn volume(&self, decode: bool) -> usize {
         let mut pos = (0, 0);
         let mut vertices = Vec::from([pos]);
-        let mut perimeter = 0;
 
         for i in self {
             let (distance, direction) = match decode {
                 true => (i.hex_distance, i.hex_direction),
                 false => (i.distance, i.direction),
             };
-            pos = direction.from(pos, distance);
+            pos = direction
+                .step_signed(pos, distance)
+                .expect("Lagoon outline overflowed");
             vertices.push(pos);
-            perimeter += distance;
         }
 
-        // Pick's Theorem (https://en.wikipedia.org/wiki/Pick%27s_theorem)
-        let interior_points = vertices.area() - (perimeter as usize - 1) / 2 + 1;
-
-        interior_points + perimeter as usize - 1
+        // Everything inside the trench is dug out along with the trench itself
+        vertices
+            .into_iter()
+            .map(|(row, col)| (row as i64, col as i64))
+            .collect::<Polygon>()
+            .lattice_points()
+            .expect("Lagoon too large to measure") as usize
     }
 }
 
 #[aoc_generator(day18)]
-fn parse(input: &str) -> Vec<Instruction> {
-    input
-        .trim()
-        .lines()
+fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parser::lines(18, input)
         .map(|line| {
-            let segments = line.split_whitespace().collect::<Vec<&str>>();
-            let direction = match segments[0] {
-                "U" => Direction::Up,
-                "D" => Direction::Down,
-                "L" => Direction::Left,
-                "R" => Direction::Right,
-                _ => panic!("Invalid direction"),
-            };
-            let distance = segments[1]
-                .parse::<isize>()
-                .expect("Unable to parse distance");
-
-            let hex = segments[2].to_string();
-            let hex_distance = isize::from_str_radix(&hex[2..7], 16).unwrap();
-            let hex_direction = match &hex[7..8] {
-                "3" => Direction::Up,
-                "1" => Direction::Down,
-                "2" => Direction::Left,
-                "0" => Direction::Right,
-                _ => panic!("Invalid direction"),
-            };
-            Instruction {
+            let mut segments = line.text.split_whitespace();
+            let direction = segments
+                .next()
+                .ok_or_else(|| line.missing("expected a direction"))?;
+            let direction = direction
+                .parse::<char>()
+                .ok()
+                .and_then(Direction::from_char)
+                .ok_or_else(|| line.error(direction, "invalid direction"))?;
+            let distance = segments
+                .next()
+                .ok_or_else(|| line.missing("expected a distance"))?;
+            let distance = line.parse::<isize>(distance, "unable to parse distance")?;
+
+            let hex = segments
+                .next()
+                .ok_or_else(|| line.missing("expected a colour code"))?;
+            let code = hex
+                .strip_prefix("(#")
+                .and_then(|h| h.strip_suffix(')'))
+                .filter(|h| h.len() == 6 && h.is_ascii())
+                .ok_or_else(|| line.error(hex, "expected colour code of the form (#rrggbb)"))?;
+            let hex_distance = isize::from_str_radix(&code[..5], 16)
+                .map_err(|_| line.error(&code[..5], "unable to parse hex distance"))?;
+            let hex_direction = code[5..]
+                .parse::<char>()
+                .ok()
+                .and_then(Direction::from_hex)
+                .ok_or_else(|| line.error(&code[5..], "invalid hex direction"))?;
+            Ok(Instruction {
                 direction,
                 distance,
                 hex_direction,
                 hex_distance,
-            }
+            })
         })
         .collect()
 }
@@ -121,9 +97,8 @@ fn part2(input: &Vec<Instruction>) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser;
-
     use super::*;
+    use test_case::test_case;
 
     const TEST: &str = "
         R 6 (#70c710)
@@ -144,20 +119,27 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(TEST)), 62);
+        assert_eq!(part1(&parse(TEST).unwrap()), 62);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(TEST)), 952408144115);
+        assert_eq!(part2(&parse(TEST).unwrap()), 952408144115);
     }
 
-    #[test]
-    fn mainline() {
-        assert_eq!(part1(&parse(&parser::load_input_string(18))), 67891);
-        assert_eq!(
-            part2(&parse(&parser::load_input_string(18))),
-            94116351948493
-        );
+    #[test_case("R 3 (#000000)\nL 3 (#000000)", 4 ; "degenerate")]
+    #[test_case("R 2 (#000000)\nD 2 (#000000)\nR 2 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 2 (#000000)\nL 2 (#000000)\nU 2 (#000000)", 17 ; "touching at a corner")]
+    fn part1_outline(input: &str, expected: usize) {
+        assert_eq!(part1(&parse(input).unwrap()), expected);
+    }
+
+    #[test_case("R 6 (#70c710)\nX 5 (#0dc571)", 2, 1, "X", "invalid direction" ; "direction")]
+    #[test_case("R 6 (#70c710)\nD 5b (#0dc571)", 2, 3, "5b", "unable to parse distance" ; "distance")]
+    #[test_case("R 6 (#70c710)\nD 5", 2, 4, "", "expected a colour code" ; "missing colour")]
+    #[test_case("R 6 (#70c71)", 1, 5, "(#70c71)", "expected colour code of the form (#rrggbb)" ; "short colour")]
+    #[test_case("R 6 (#70c716)", 1, 12, "6", "invalid hex direction" ; "hex direction")]
+    fn parse_error(input: &str, line: usize, column: usize, token: &str, reason: &str) {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err, ParseError::new(18, line, column, token, reason));
     }
 }
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::parser::{self, Line, ParseError};
+
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
 enum Category {
     X,
@@ -35,6 +37,81 @@ impl PartRating {
     }
 }
 
+/// Inclusive ranges of ratings for each category, covering every part which could take
+/// the same route through the workflows.
+#[derive(Debug, PartialEq, Clone)]
+struct PartRange {
+    x: (usize, usize),
+    m: (usize, usize),
+    a: (usize, usize),
+    s: (usize, usize),
+}
+
+impl PartRange {
+    fn new(min: usize, max: usize) -> Self {
+        Self {
+            x: (min, max),
+            m: (min, max),
+            a: (min, max),
+            s: (min, max),
+        }
+    }
+
+    fn get_mut(&mut self, category: &Category) -> &mut (usize, usize) {
+        match category {
+            Category::X => &mut self.x,
+            Category::M => &mut self.m,
+            Category::A => &mut self.a,
+            Category::S => &mut self.s,
+        }
+    }
+
+    /// Number of distinct parts within the range
+    fn combinations(&self) -> usize {
+        [self.x, self.m, self.a, self.s]
+            .iter()
+            .map(|&(min, max)| (max + 1).saturating_sub(min))
+            .product()
+    }
+
+    /// Split into the parts which satisfy a rule's condition and the parts which don't.
+    /// Either side is `None` if no parts fall into it.
+    fn split(&self, rule: &Rule) -> (Option<PartRange>, Option<PartRange>) {
+        let (Some(category), Some(comparator), Some(value)) =
+            (&rule.category, &rule.comparator, rule.value)
+        else {
+            // A rule without a condition applies to everything
+            return (Some(self.clone()), None);
+        };
+
+        let (min, max) = match category {
+            Category::X => self.x,
+            Category::M => self.m,
+            Category::A => self.a,
+            Category::S => self.s,
+        };
+        let (matched, unmatched) = match comparator {
+            Comparator::GreaterThan => (
+                (min.max(value.saturating_add(1)), max),
+                (min, max.min(value)),
+            ),
+            Comparator::LessThan => (
+                (min, max.min(value.saturating_sub(1))),
+                (min.max(value), max),
+            ),
+        };
+
+        let with = |(min, max): (usize, usize)| {
+            (min <= max).then(|| {
+                let mut range = self.clone();
+                *range.get_mut(category) = (min, max);
+                range
+            })
+        };
+        (with(matched), with(unmatched))
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 struct Rule {
     category: Option<Category>,
@@ -44,64 +121,70 @@ struct Rule {
     send_to: Option<String>,
 }
 
+#[derive(Debug)]
 struct Input {
     workflows: HashMap<String, Vec<Rule>>,
     parts: Vec<PartRating>,
 }
 
-fn parse_part_rating(line: &str) -> PartRating {
-    let trimmed_line = line
-        .trim_matches(['{', '}'])
-        .split(',')
-        .collect::<Vec<&str>>();
+fn parse_category<'a>(line: &Line<'a>, token: &'a str) -> Result<Category, ParseError> {
+    match token {
+        "x" => Ok(Category::X),
+        "m" => Ok(Category::M),
+        "a" => Ok(Category::A),
+        "s" => Ok(Category::S),
+        _ => Err(line.error(token, "invalid category")),
+    }
+}
 
-    let ratings: HashMap<Category, usize> = trimmed_line
-        .iter()
-        .map(|rating| {
-            let equation = rating.split('=').collect::<Vec<&str>>();
-            let category = match *equation
-                .first()
-                .expect("Failed to parse part rating category")
-            {
-                "x" => Category::X,
-                "m" => Category::M,
-                "a" => Category::A,
-                "s" => Category::S,
-                _ => panic!("Invalid category"),
-            };
-            let value = equation
-                .get(1)
-                .expect("Failed to get part rating value")
-                .parse::<usize>()
-                .expect("Failed to parse part rating value");
-            (category, value)
-        })
-        .collect();
+fn parse_outcome(outcome: &str) -> (Outcome, Option<String>) {
+    match outcome {
+        "A" => (Outcome::Accept, None),
+        "R" => (Outcome::Reject, None),
+        _ => (Outcome::Send, Some(outcome.to_string())),
+    }
+}
+
+fn parse_part_rating(line: &Line) -> Result<PartRating, ParseError> {
+    let mut ratings = HashMap::new();
 
-    PartRating {
-        x: *ratings.get(&Category::X).unwrap(),
-        m: *ratings.get(&Category::M).unwrap(),
-        a: *ratings.get(&Category::A).unwrap(),
-        s: *ratings.get(&Category::S).unwrap(),
+    for rating in line.text.trim_matches(['{', '}']).split(',') {
+        let (category, value) = rating
+            .split_once('=')
+            .ok_or_else(|| line.error(rating, "expected part rating of the form category=value"))?;
+        let category = parse_category(line, category)?;
+        let value = line.parse::<usize>(value, "failed to parse part rating value")?;
+        ratings.insert(category, value);
     }
+
+    let rating = |category| {
+        ratings
+            .get(&category)
+            .copied()
+            .ok_or_else(|| line.error(line.text, "expected ratings for x, m, a and s"))
+    };
+
+    Ok(PartRating {
+        x: rating(Category::X)?,
+        m: rating(Category::M)?,
+        a: rating(Category::A)?,
+        s: rating(Category::S)?,
+    })
 }
 
-fn parse_workflow(line: &str) -> (String, Vec<Rule>) {
-    // TODO fixup verbose input parsing
+fn parse_workflow(line: &Line) -> Result<(String, Vec<Rule>), ParseError> {
     let mut rules = Vec::new();
-    let n = line.split('{').collect::<Vec<&str>>();
-    let name = n.first().expect("Expected workflow name");
-    let workflow = n
-        .get(1)
-        .expect("Expected workflow instructions")
-        .trim_matches('}');
-    for instruct in workflow.split(',') {
-        if !instruct.contains('<') && !instruct.contains('>') {
-            let (outcome, send_to) = match instruct {
-                "A" => (Outcome::Accept, None),
-                "R" => (Outcome::Reject, None),
-                _ => (Outcome::Send, Some(instruct.to_string())),
-            };
+    let (name, workflow) = line
+        .text
+        .split_once('{')
+        .ok_or_else(|| line.missing("expected workflow instructions"))?;
+    let instructs = workflow
+        .trim_end_matches('}')
+        .split(',')
+        .collect::<Vec<&str>>();
+    for instruct in &instructs {
+        let Some((condition, outcome)) = instruct.split_once(':') else {
+            let (outcome, send_to) = parse_outcome(instruct);
             rules.push(Rule {
                 category: None,
                 comparator: None,
@@ -109,53 +192,49 @@ fn parse_workflow(line: &str) -> (String, Vec<Rule>) {
                 outcome,
                 send_to,
             });
-        } else {
-            let mut chars = instruct.chars();
-            let category = match chars
-                .next()
-                .expect("Expected instruction to start with part category")
-            {
-                'x' => Some(Category::X),
-                'm' => Some(Category::M),
-                'a' => Some(Category::A),
-                's' => Some(Category::S),
-                _ => panic!("Invalid category"),
-            };
-            let comparator = match chars
-                .next()
-                .expect("Expected instruction to contain a comparator")
-            {
-                '<' => Some(Comparator::LessThan),
-                '>' => Some(Comparator::GreaterThan),
-                _ => panic!("Invalid comparator"),
-            };
-            let segment = &instruct[2..].split(':').collect::<Vec<&str>>();
-            let value = Some(
-                segment
-                    .first()
-                    .expect("Expected instruction to have a value")
-                    .parse::<usize>()
-                    .expect("Unable to parse value"),
-            );
-            let outcome_str = segment
-                .get(1)
-                .expect("Expected instruction to have a outcome");
-            let (outcome, send_to) = match *outcome_str {
-                "A" => (Outcome::Accept, None),
-                "R" => (Outcome::Reject, None),
-                _ => (Outcome::Send, Some(outcome_str.to_string())),
-            };
-            rules.push(Rule {
-                category,
-                comparator,
-                value,
-                outcome,
-                send_to,
-            });
-        }
+            continue;
+        };
+
+        let split = condition
+            .find(['<', '>'])
+            .ok_or_else(|| line.error(condition, "expected instruction to contain a comparator"))?;
+        let category = parse_category(line, &condition[..split])?;
+        let comparator = match &condition[split..split + 1] {
+            "<" => Comparator::LessThan,
+            _ => Comparator::GreaterThan,
+        };
+        let value = line.parse::<usize>(&condition[split + 1..], "unable to parse value")?;
+        let (outcome, send_to) = parse_outcome(outcome);
+        rules.push(Rule {
+            category: Some(category),
+            comparator: Some(comparator),
+            value: Some(value),
+            outcome,
+            send_to,
+        });
+    }
+
+    // Parts which match no condition would otherwise have nowhere to go
+    if let Some(last) = instructs.last().filter(|i| i.contains(':')) {
+        return Err(line.error(last, "expected a final fallback rule"));
     }
 
-    (name.to_string(), rules)
+    Ok((name.to_string(), rules))
+}
+
+/// Each workflow a line of workflows sends parts to, as written.
+fn targets<'a>(line: &Line<'a>) -> impl Iterator<Item = &'a str> {
+    let text = line.text;
+    let rules = text.split_once('{').map_or("", |(_, rules)| rules);
+    rules
+        .trim_end_matches('}')
+        .split(',')
+        .map(|instruct| {
+            instruct
+                .split_once(':')
+                .map_or(instruct, |(_, outcome)| outcome)
+        })
+        .filter(|outcome| !matches!(*outcome, "A" | "R"))
 }
 
 trait Workflow {
@@ -196,26 +275,42 @@ impl Workflow for Vec<Rule> {
 }
 
 #[aoc_generator(day19)]
-fn parse(input: &str) -> Input {
+fn parse(input: &str) -> Result<Input, ParseError> {
     let mut workflows = HashMap::new();
+    let mut workflow_lines = Vec::new();
     let mut parts = Vec::new();
 
-    for entry in input.trim().lines() {
-        let line = entry.trim();
-        if line.starts_with("{") {
+    for line in parser::lines(19, input) {
+        if line.text.starts_with('{') {
             // It's a part rating
-            parts.push(parse_part_rating(line));
-        } else if line.is_empty() {
+            parts.push(parse_part_rating(&line)?);
+        } else if line.text.is_empty() {
             // It's the empty line between workflows and part ratings
             continue;
         } else {
             // It's a workflow
-            let workflow = parse_workflow(line);
+            let workflow = parse_workflow(&line)?;
             workflows.insert(workflow.0, workflow.1);
+            workflow_lines.push(line);
         }
     }
 
-    Input { workflows, parts }
+    for line in &workflow_lines {
+        if let Some(target) = targets(line).find(|t| !workflows.contains_key(*t)) {
+            return Err(line.error(target, "unknown workflow"));
+        }
+    }
+    if !workflows.contains_key("in") {
+        return Err(ParseError::new(
+            19,
+            1,
+            1,
+            "",
+            "expected a workflow named in",
+        ));
+    }
+
+    Ok(Input { workflows, parts })
 }
 
 #[aoc(day19, part1)]
@@ -242,8 +337,40 @@ fn part1(input: &Input) -> usize {
 }
 
 #[aoc(day19, part2)]
-fn part2(_input: &Input) -> usize {
-    todo!()
+fn part2(input: &Input) -> usize {
+    let mut result = 0;
+    let mut pending = vec![("in".to_string(), PartRange::new(1, 4000))];
+
+    while let Some((name, range)) = pending.pop() {
+        let workflow = input
+            .workflows
+            .get(&name)
+            .expect("Expected workflow to exist");
+
+        // Each rule takes the parts matching it, leaving the rest for the next rule
+        let mut remaining = Some(range);
+        for rule in workflow {
+            let Some(range) = remaining.take() else {
+                break;
+            };
+            let (matched, unmatched) = range.split(rule);
+            remaining = unmatched;
+
+            let Some(matched) = matched else {
+                continue;
+            };
+            match rule.outcome {
+                Outcome::Accept => result += matched.combinations(),
+                Outcome::Reject => (),
+                Outcome::Send => {
+                    let send_to = rule.send_to.clone().expect("Expected send_to to be Some");
+                    pending.push((send_to, matched));
+                }
+            }
+        }
+    }
+
+    result
 }
 
 #[cfg(test)]
@@ -272,6 +399,10 @@ mod tests {
         {x=2127,m=1623,a=2188,s=1013}
     ";
 
+    fn line(input: &str) -> Line<'_> {
+        parser::lines(19, input).next().unwrap()
+    }
+
     #[test_case("{x=787,m=2655,a=1222,s=2876}", PartRating{
         x: 787,
         m: 2655,
@@ -279,12 +410,12 @@ mod tests {
         s: 2876,
         }; "a")]
     fn part_ratings(input: &str, answer: PartRating) {
-        assert_eq!(parse_part_rating(input), answer);
+        assert_eq!(parse_part_rating(&line(input)).unwrap(), answer);
     }
 
     #[test_case("px{a<2006:qkq,m>2090:A,rfg}", (
         "px".to_string(), 
-        Vec::from(vec![
+        vec![
             Rule{
                 category: Some(Category::A),
                 comparator: Some(Comparator::LessThan),
@@ -306,19 +437,56 @@ mod tests {
                 outcome: Outcome::Send,
                 send_to: Some("rfg".to_string())
             }
-        ]))
+        ])
         ; "a")]
     fn workflow(input: &str, answer: (String, Vec<Rule>)) {
-        assert_eq!(parse_workflow(input), answer);
+        assert_eq!(parse_workflow(&line(input)).unwrap(), answer);
+    }
+
+    #[test_case("px{a<2006:qkq,m>2090:A,rfg}\n\n{x=787,m=2655,a=1222,s=2876}\n{x=1679,m=44,a=2o67,s=496}", 4, 16, "2o67", "failed to parse part rating value" ; "rating value")]
+    #[test_case("{x=787,m=2655,a=1222}", 1, 1, "{x=787,m=2655,a=1222}", "expected ratings for x, m, a and s" ; "missing rating")]
+    #[test_case("px{a<2006:qkq,q>2090:A,rfg}", 1, 15, "q", "invalid category" ; "category")]
+    #[test_case("px{a<2006:qkq,m=2090:A,rfg}", 1, 15, "m=2090", "expected instruction to contain a comparator" ; "comparator")]
+    #[test_case("px{a<20x6:qkq,m>2090:A,rfg}", 1, 6, "20x6", "unable to parse value" ; "value")]
+    #[test_case("in{a<2006:A,m>2090:R}", 1, 13, "m>2090:R", "expected a final fallback rule" ; "no fallback")]
+    #[test_case("in{a<2006:px,R}\npx{m>2090:A,qs}", 2, 13, "qs", "unknown workflow" ; "unknown workflow")]
+    #[test_case("px{a<2006:A,R}\n\n{x=787,m=2655,a=1222,s=2876}", 1, 1, "", "expected a workflow named in" ; "no in")]
+    fn parse_error(input: &str, line: usize, column: usize, token: &str, reason: &str) {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err, ParseError::new(19, line, column, token, reason));
     }
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(TEST)), 19114);
+        assert_eq!(part1(&parse(TEST).unwrap()), 19114);
     }
 
     #[test]
-    fn mainline() {
-        assert_eq!(part1(&parse(&parser::load_input_string(19))), 382440);
+    fn part2_example() {
+        assert_eq!(part2(&parse(TEST).unwrap()), 167409079868000);
+    }
+
+    #[test_case("a<2006", Some((1, 2005)), Some((2006, 4000)) ; "less than")]
+    #[test_case("a>2006", Some((2007, 4000)), Some((1, 2006)) ; "greater than")]
+    #[test_case("a<1", None, Some((1, 4000)) ; "nothing matches")]
+    #[test_case("a>0", Some((1, 4000)), None ; "everything matches")]
+    #[test_case("a<0", None, Some((1, 4000)) ; "below every value")]
+    #[test_case("a>18446744073709551615", None, Some((1, 4000)) ; "above every value")]
+    fn split_range(
+        condition: &str,
+        matched: Option<(usize, usize)>,
+        unmatched: Option<(usize, usize)>,
+    ) {
+        let (_, rules) = parse_workflow(&line(&format!("px{{{}:A,R}}", condition))).unwrap();
+        let (m, u) = PartRange::new(1, 4000).split(&rules[0]);
+        // Only the category in the condition is narrowed
+        for range in m.iter().chain(u.iter()) {
+            assert_eq!(
+                (range.x, range.m, range.s),
+                ((1, 4000), (1, 4000), (1, 4000))
+            );
+        }
+        assert_eq!(m.map(|r| r.a), matched);
+        assert_eq!(u.map(|r| r.a), unmatched);
     }
 }
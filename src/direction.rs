@@ -0,0 +1,202 @@
+use crate::grid::Point;
+
+/// One of the four cardinal directions. North points up the page, towards row 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+/// One of the four diagonal directions between the cardinal ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Ordinal {
+    NorthEast,
+    SouthEast,
+    SouthWest,
+    NorthWest,
+}
+
+impl Direction {
+    /// Clockwise from north
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// Parse `U/D/L/R` or `N/E/S/W`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'U' | 'N' => Some(Direction::North),
+            'R' | 'E' => Some(Direction::East),
+            'D' | 'S' => Some(Direction::South),
+            'L' | 'W' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    /// Parse the last hex digit of a day 18 colour code, where 0 means right and the
+    /// digits continue clockwise.
+    pub fn from_hex(c: char) -> Option<Self> {
+        match c {
+            '0' => Some(Direction::East),
+            '1' => Some(Direction::South),
+            '2' => Some(Direction::West),
+            '3' => Some(Direction::North),
+            _ => None,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.turn_left().reverse()
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// Change in `(row, col)` for a single step.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+
+    /// Move a grid point `n` cells, or `None` if it would leave the range of `usize`.
+    pub fn step(self, pos: Point, n: usize) -> Option<Point> {
+        step(pos, self.offset(), n)
+    }
+
+    /// Move a signed `(row, col)` point `n` cells, or `None` on overflow.
+    pub fn step_signed(self, pos: (isize, isize), n: isize) -> Option<(isize, isize)> {
+        step_signed(pos, self.offset(), n)
+    }
+}
+
+impl Ordinal {
+    /// Clockwise from north east
+    pub const ALL: [Ordinal; 4] = [
+        Ordinal::NorthEast,
+        Ordinal::SouthEast,
+        Ordinal::SouthWest,
+        Ordinal::NorthWest,
+    ];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Ordinal::NorthEast => Ordinal::NorthWest,
+            Ordinal::SouthEast => Ordinal::NorthEast,
+            Ordinal::SouthWest => Ordinal::SouthEast,
+            Ordinal::NorthWest => Ordinal::SouthWest,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.turn_left().reverse()
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Ordinal::NorthEast => Ordinal::SouthWest,
+            Ordinal::SouthEast => Ordinal::NorthWest,
+            Ordinal::SouthWest => Ordinal::NorthEast,
+            Ordinal::NorthWest => Ordinal::SouthEast,
+        }
+    }
+
+    /// Change in `(row, col)` for a single step.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Ordinal::NorthEast => (-1, 1),
+            Ordinal::SouthEast => (1, 1),
+            Ordinal::SouthWest => (1, -1),
+            Ordinal::NorthWest => (-1, -1),
+        }
+    }
+
+    /// Move a grid point `n` cells, or `None` if it would leave the range of `usize`.
+    pub fn step(self, pos: Point, n: usize) -> Option<Point> {
+        step(pos, self.offset(), n)
+    }
+
+    /// Move a signed `(row, col)` point `n` cells, or `None` on overflow.
+    pub fn step_signed(self, pos: (isize, isize), n: isize) -> Option<(isize, isize)> {
+        step_signed(pos, self.offset(), n)
+    }
+}
+
+fn step((row, col): Point, (dr, dc): (isize, isize), n: usize) -> Option<Point> {
+    let axis = |v: usize, d: isize| match d {
+        -1 => v.checked_sub(n),
+        1 => v.checked_add(n),
+        _ => Some(v),
+    };
+    Some((axis(row, dr)?, axis(col, dc)?))
+}
+
+fn step_signed(
+    (row, col): (isize, isize),
+    (dr, dc): (isize, isize),
+    n: isize,
+) -> Option<(isize, isize)> {
+    Some((
+        row.checked_add(dr.checked_mul(n)?)?,
+        col.checked_add(dc.checked_mul(n)?)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Ordinal::NorthEast.turn_right(), Ordinal::SouthEast);
+        assert_eq!(Ordinal::NorthEast.reverse(), Ordinal::SouthWest);
+    }
+
+    #[test]
+    fn stepping() {
+        assert_eq!(Direction::North.step((3, 3), 2), Some((1, 3)));
+        assert_eq!(Direction::North.step((3, 3), 4), None);
+        assert_eq!(Direction::East.step((0, usize::MAX), 1), None);
+        assert_eq!(Ordinal::SouthWest.step((3, 3), 3), Some((6, 0)));
+        assert_eq!(Direction::West.step_signed((0, 0), 5), Some((0, -5)));
+        assert_eq!(Direction::South.step_signed((isize::MAX, 0), 1), None);
+        assert_eq!(Direction::North.step_signed((0, 0), isize::MIN), None);
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(Direction::from_char('U'), Some(Direction::North));
+        assert_eq!(Direction::from_char('W'), Some(Direction::West));
+        assert_eq!(Direction::from_char('x'), None);
+        assert_eq!(Direction::from_hex('1'), Some(Direction::South));
+        assert_eq!(Direction::from_hex('4'), None);
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+
+use num_integer::gcd;
+
+/// A lattice point. Days using grid coordinates pass `(row, col)`, which keeps the
+/// orientation the same as it appears on the page.
+pub type Vertex = (i64, i64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    Anticlockwise,
+    /// The outline encloses no area, e.g. it is a single line traced out and back
+    Degenerate,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A closed polygon with vertices on the integer lattice. The outline runs from each
+/// vertex to the next and from the last back to the first.
+///
+/// Everything is computed with checked 128-bit arithmetic, so methods which count return
+/// `None` rather than a wrapped value when the answer doesn't fit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Vertex>,
+}
+
+impl Polygon {
+    /// Build a polygon from its outline. A final vertex repeating the first is dropped.
+    pub fn new(mut vertices: Vec<Vertex>) -> Self {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    /// Pairs of consecutive vertices, including the closing edge.
+    fn edges(&self) -> impl Iterator<Item = (Vertex, Vertex)> + '_ {
+        edges(&self.vertices)
+    }
+
+    /// Twice the signed area from the Shoelace Formula (https://en.wikipedia.org/wiki/Shoelace_formula),
+    /// which keeps the result an integer. Positive when the vertices run anticlockwise.
+    pub fn double_signed_area(&self) -> Option<i128> {
+        double_signed_area(&self.vertices)
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        match self.double_signed_area().map(i128::signum) {
+            Some(1) => Orientation::Anticlockwise,
+            Some(-1) => Orientation::Clockwise,
+            _ => Orientation::Degenerate,
+        }
+    }
+
+    /// Number of lattice points on the outline. A point is counted each time the outline
+    /// passes through it, so for a simple polygon this is the distinct boundary points.
+    pub fn boundary_points(&self) -> Option<u64> {
+        boundary_points(&self.vertices)
+    }
+
+    /// Number of lattice points strictly inside the polygon, from Pick's Theorem
+    /// (https://en.wikipedia.org/wiki/Pick%27s_theorem).
+    ///
+    /// Outlines which touch themselves at a vertex are split there into separate loops:
+    /// each loop running the same way as the whole polygon is a region of its own, while
+    /// one running the other way is a hole pinched off from the rest. A loop enclosing no
+    /// area is a spur traced out and back, which takes its points from the interior if it
+    /// runs into the polygon. Degenerate polygons have nothing inside. `None` for outlines
+    /// which cross themselves or double back along an edge without returning to a vertex,
+    /// as they aren't supported.
+    pub fn interior_points(&self) -> Option<u64> {
+        let area = self.double_signed_area()?;
+        if area == 0 {
+            return Some(0);
+        }
+        let mut regions = 0i128;
+        let mut boundary = 0i128;
+        let (spurs, loops): (Vec<_>, Vec<_>) = self
+            .loops()
+            .into_iter()
+            .partition(|l| double_signed_area(l) == Some(0));
+        for l in &loops {
+            let loop_area = double_signed_area(l)?;
+            if retraces(l)? {
+                return None;
+            }
+            regions += (loop_area.signum() == area.signum()) as i128;
+            boundary = boundary.checked_add(i128::from(boundary_points(l)?))?;
+        }
+        for spur in spurs.iter().filter(|s| s.len() > 1) {
+            // Which side of the rest of the outline the middle of the spur's first edge is on,
+            // in doubled coordinates to keep the middle on the lattice
+            let double = |(x, y): Vertex| (2 * i128::from(x), 2 * i128::from(y));
+            let ((ax, ay), (bx, by)) = (wide(spur[0]), wide(spur[1]));
+            let middle = (ax + bx, ay + by);
+            let rest = loops
+                .iter()
+                .flat_map(|l| edges(l))
+                .map(|(a, b)| (double(a), double(b)));
+            match locate(rest, middle)? {
+                Location::Inside => {
+                    boundary = boundary.checked_add(i128::from(boundary_points(spur)?))?
+                }
+                Location::Outside => (),
+                Location::Boundary => return None,
+            }
+        }
+        // 2A = 2I + B - 2 for each region
+        let interior = area.abs().checked_sub(boundary)?.checked_add(2 * regions)? / 2;
+        u64::try_from(interior).ok()
+    }
+
+    /// Number of lattice points inside or on the outline, `A + B/2 + 1`. As well as simple
+    /// polygons this holds for outlines which double back along themselves or touch at a
+    /// vertex without enclosing a hole, counting each point only once.
+    pub fn lattice_points(&self) -> Option<u64> {
+        let area = self.double_signed_area()?.abs();
+        let boundary = i128::from(self.boundary_points()?);
+        u64::try_from((area.checked_add(boundary)?.checked_add(2)?) / 2).ok()
+    }
+
+    /// Whether a point lies inside, on or outside the outline, by counting crossings of a
+    /// ray cast in the direction of increasing first coordinate. `None` if the cross product
+    /// of an edge with the point doesn't fit in an `i128`.
+    pub fn locate(&self, point: Vertex) -> Option<Location> {
+        locate(self.edges().map(|(a, b)| (wide(a), wide(b))), wide(point))
+    }
+
+    /// Split the outline into loops wherever it returns to a vertex it has already visited.
+    fn loops(&self) -> Vec<Vec<Vertex>> {
+        let mut loops = Vec::new();
+        let mut path: Vec<Vertex> = Vec::new();
+        let mut seen = HashMap::new();
+        for &vertex in &self.vertices {
+            match seen.get(&vertex) {
+                Some(&start) => {
+                    // The loop runs from the earlier visit back round to this one
+                    let mut closed = vec![vertex];
+                    closed.extend(path.drain(start + 1..));
+                    for v in &closed[1..] {
+                        seen.remove(v);
+                    }
+                    loops.push(closed);
+                }
+                None => {
+                    seen.insert(vertex, path.len());
+                    path.push(vertex);
+                }
+            }
+        }
+        loops.push(path);
+        loops
+    }
+}
+
+impl FromIterator<Vertex> for Polygon {
+    fn from_iter<I: IntoIterator<Item = Vertex>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Pairs of consecutive vertices, including the closing edge.
+fn edges(vertices: &[Vertex]) -> impl Iterator<Item = (Vertex, Vertex)> + '_ {
+    let next = vertices.iter().cycle().skip(1);
+    vertices.iter().copied().zip(next.copied())
+}
+
+fn wide((x, y): Vertex) -> (i128, i128) {
+    (i128::from(x), i128::from(y))
+}
+
+fn boundary_points(vertices: &[Vertex]) -> Option<u64> {
+    edges(vertices).try_fold(0u64, |acc, (a, b)| {
+        acc.checked_add(gcd(a.0.abs_diff(b.0), a.1.abs_diff(b.1)))
+    })
+}
+
+/// Where a point lies relative to the edges, by counting crossings of a ray cast in the
+/// direction of increasing first coordinate.
+fn locate(
+    edges: impl Iterator<Item = ((i128, i128), (i128, i128))>,
+    (px, py): (i128, i128),
+) -> Option<Location> {
+    let mut inside = false;
+    for ((ax, ay), (bx, by)) in edges {
+        let cross = (bx - ax)
+            .checked_mul(py - ay)?
+            .checked_sub((px - ax).checked_mul(by - ay)?)?;
+        if cross == 0
+            && (ax.min(bx)..=ax.max(bx)).contains(&px)
+            && (ay.min(by)..=ay.max(by)).contains(&py)
+        {
+            return Some(Location::Boundary);
+        }
+        if (ay > py) != (by > py) && (cross > 0) == (by > ay) {
+            inside = !inside;
+        }
+    }
+    match inside {
+        true => Some(Location::Inside),
+        false => Some(Location::Outside),
+    }
+}
+
+/// Whether the outline turns straight back on itself at any vertex.
+fn retraces(vertices: &[Vertex]) -> Option<bool> {
+    let n = vertices.len();
+    for i in 0..n {
+        let (a, b, c) = (vertices[i], vertices[(i + 1) % n], vertices[(i + 2) % n]);
+        let (dx1, dy1) = (
+            i128::from(b.0) - i128::from(a.0),
+            i128::from(b.1) - i128::from(a.1),
+        );
+        let (dx2, dy2) = (
+            i128::from(c.0) - i128::from(b.0),
+            i128::from(c.1) - i128::from(b.1),
+        );
+        let cross = dx1.checked_mul(dy2)?.checked_sub(dy1.checked_mul(dx2)?)?;
+        let dot = dx1.checked_mul(dx2)?.checked_add(dy1.checked_mul(dy2)?)?;
+        if cross == 0 && dot < 0 {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+fn double_signed_area(vertices: &[Vertex]) -> Option<i128> {
+    let next = vertices.iter().cycle().skip(1);
+    vertices.iter().zip(next).try_fold(0i128, |acc, (a, b)| {
+        let term = i128::from(a.0) * i128::from(b.1) - i128::from(b.0) * i128::from(a.1);
+        acc.checked_add(term)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn square(size: i64) -> Polygon {
+        Polygon::new(vec![(0, 0), (size, 0), (size, size), (0, size), (0, 0)])
+    }
+
+    #[test]
+    fn square_counts() {
+        let square = square(4);
+        assert_eq!(square.vertices().len(), 4);
+        assert_eq!(square.double_signed_area(), Some(32));
+        assert_eq!(square.orientation(), Orientation::Anticlockwise);
+        assert_eq!(square.boundary_points(), Some(16));
+        assert_eq!(square.interior_points(), Some(9));
+        assert_eq!(square.lattice_points(), Some(25));
+
+        let reversed = square.vertices().iter().rev().copied().collect::<Polygon>();
+        assert_eq!(reversed.double_signed_area(), Some(-32));
+        assert_eq!(reversed.orientation(), Orientation::Clockwise);
+        assert_eq!(reversed.interior_points(), Some(9));
+    }
+
+    #[test]
+    fn diagonal_edges() {
+        let triangle = Polygon::new(vec![(0, 0), (4, 0), (0, 4)]);
+        assert_eq!(triangle.double_signed_area(), Some(16));
+        assert_eq!(triangle.boundary_points(), Some(12));
+        assert_eq!(triangle.interior_points(), Some(3));
+    }
+
+    #[test_case(vec![] ; "empty")]
+    #[test_case(vec![(2, 3)] ; "point")]
+    #[test_case(vec![(0, 0), (3, 0)] ; "segment")]
+    #[test_case(vec![(0, 0), (1, 1), (3, 3)] ; "collinear")]
+    #[test_case(vec![(0, 0), (3, 0), (3, 2), (3, 0)] ; "doubling back")]
+    fn degenerate(vertices: Vec<Vertex>) {
+        let polygon = Polygon::new(vertices);
+        assert_eq!(polygon.double_signed_area(), Some(0));
+        assert_eq!(polygon.orientation(), Orientation::Degenerate);
+        assert_eq!(polygon.interior_points(), Some(0));
+        assert_ne!(polygon.locate((1, 2)), Some(Location::Inside));
+    }
+
+    #[test]
+    fn doubling_back() {
+        // A square with a spur running out to (6, 4) and back
+        let spur = Polygon::new(vec![(0, 0), (4, 0), (4, 4), (6, 4), (4, 4), (0, 4)]);
+        assert_eq!(spur.double_signed_area(), Some(32));
+        assert_eq!(spur.boundary_points(), Some(20));
+        assert_eq!(spur.interior_points(), Some(9));
+        assert_eq!(spur.lattice_points(), Some(27));
+        let from_tip = Polygon::new(vec![(6, 4), (4, 4), (0, 4), (0, 0), (4, 0), (4, 4)]);
+        assert_eq!(from_tip.interior_points(), Some(9));
+
+        // Running into the square instead, the spur's points are no longer inside
+        let inward = Polygon::new(vec![(0, 0), (4, 0), (4, 4), (2, 4), (2, 2), (2, 4), (0, 4)]);
+        assert_eq!(inward.interior_points(), Some(7));
+
+        // The same spur coming back along the edge past the vertex it left from
+        let overlap = Polygon::new(vec![(0, 0), (4, 0), (4, 4), (6, 4), (2, 4), (0, 4)]);
+        assert_eq!(overlap.lattice_points(), Some(27));
+        assert_eq!(overlap.interior_points(), None);
+    }
+
+    #[test]
+    fn degenerate_segment() {
+        let segment = Polygon::new(vec![(0, 0), (3, 0)]);
+        assert_eq!(segment.boundary_points(), Some(6));
+        assert_eq!(segment.lattice_points(), Some(4));
+        assert_eq!(segment.locate((2, 0)), Some(Location::Boundary));
+        assert_eq!(segment.locate((4, 0)), Some(Location::Outside));
+    }
+
+    #[test]
+    fn touching_regions() {
+        // Two squares meeting at the corner (2, 2), traced as one outline
+        let polygon = Polygon::new(vec![
+            (0, 0),
+            (2, 0),
+            (2, 2),
+            (4, 2),
+            (4, 4),
+            (2, 4),
+            (2, 2),
+            (0, 2),
+        ]);
+        assert_eq!(polygon.double_signed_area(), Some(16));
+        assert_eq!(polygon.boundary_points(), Some(16));
+        assert_eq!(polygon.interior_points(), Some(2));
+        assert_eq!(polygon.lattice_points(), Some(17));
+        assert_eq!(polygon.locate((2, 2)), Some(Location::Boundary));
+        assert_eq!(polygon.locate((3, 3)), Some(Location::Inside));
+        assert_eq!(polygon.locate((1, 3)), Some(Location::Outside));
+    }
+
+    #[test]
+    fn pinched_hole() {
+        // A square with a slit running in from the left to a square hole
+        let polygon = Polygon::new(vec![
+            (0, 0),
+            (6, 0),
+            (6, 6),
+            (0, 6),
+            (0, 3),
+            (2, 3),
+            (2, 4),
+            (4, 4),
+            (4, 2),
+            (2, 2),
+            (2, 3),
+            (0, 3),
+        ]);
+        assert_eq!(polygon.double_signed_area(), Some(64));
+        assert_eq!(polygon.orientation(), Orientation::Anticlockwise);
+        assert_eq!(polygon.interior_points(), Some(15));
+        assert_eq!(polygon.locate((1, 1)), Some(Location::Inside));
+        assert_eq!(polygon.locate((1, 3)), Some(Location::Boundary));
+        assert_eq!(polygon.locate((3, 3)), Some(Location::Outside));
+    }
+
+    #[test]
+    fn overflow() {
+        let huge = square(i64::MAX);
+        assert!(huge.double_signed_area().is_some());
+        assert_eq!(huge.boundary_points(), None);
+        assert_eq!(huge.interior_points(), None);
+        let large = square(i64::MAX / 4);
+        assert_eq!(large.boundary_points(), Some((i64::MAX / 4) as u64 * 4));
+        assert_eq!(large.interior_points(), None);
+        assert_eq!(large.locate((1, 1)), Some(Location::Inside));
+        let wide = Polygon::new(vec![
+            (i64::MIN, i64::MIN),
+            (i64::MAX, i64::MIN),
+            (i64::MAX, i64::MAX),
+        ]);
+        assert_eq!(wide.locate((i64::MIN, i64::MAX)), None);
+        assert_eq!(wide.locate((0, 0)), Some(Location::Boundary));
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    direction::{Direction, Ordinal},
+    parser::{self, Line, ParseError},
+};
+
+/// Position within a grid as `(row, col)`, with `(0, 0)` at the top left.
+pub type Point = (usize, usize);
+
+/// A dense, rectangular grid stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Build a grid from cells laid out row by row.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "Grid cells don't fit");
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    /// Parse each non-blank line of the input into a row. Every row must be the same width.
+    pub fn parse_rows<F>(day: usize, input: &str, mut f: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(&Line) -> Result<Vec<T>, ParseError>,
+    {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for line in parser::lines(day, input) {
+            let row = f(&line)?;
+            if *width.get_or_insert(row.len()) != row.len() {
+                return Err(line.missing("expected all rows to be the same width"));
+            }
+            cells.extend(row);
+            height += 1;
+        }
+
+        Ok(Self::new(width.unwrap_or(0), height, cells))
+    }
+
+    /// Parse a grid with one cell per character. The mapping returns the reason a
+    /// character is invalid, which is reported against its line and column.
+    pub fn parse<F>(day: usize, input: &str, mut f: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, &'static str>,
+    {
+        Self::parse_rows(day, input, |line| {
+            line.text
+                .char_indices()
+                .map(|(col, c)| f(c).map_err(|reason| line.error_at(col, reason)))
+                .collect()
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Position of the bottom right cell.
+    pub fn last(&self) -> Point {
+        (self.height.saturating_sub(1), self.width.saturating_sub(1))
+    }
+
+    pub fn contains(&self, (row, col): Point) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    /// Swap the contents of two cells.
+    pub fn swap(&mut self, a: Point, b: Point) {
+        assert!(self.contains(a) && self.contains(b), "Point outside grid");
+        self.cells
+            .swap(a.0 * self.width + a.1, b.0 * self.width + b.1);
+    }
+
+    /// In-bounds neighbours sharing an edge, in the order north, east, south, west.
+    pub fn neighbours4(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| dir.step(pos, 1))
+            .filter(|pos| self.contains(*pos))
+    }
+
+    /// In-bounds neighbours sharing an edge or a corner, the four edges first.
+    pub fn neighbours8(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        let diagonals = Ordinal::ALL
+            .into_iter()
+            .filter_map(move |dir| dir.step(pos, 1))
+            .filter(|pos| self.contains(*pos));
+        self.neighbours4(pos).chain(diagonals)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column outside grid");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Every cell alongside its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / width, i % width), cell))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Build a new grid where cell `(row, col)` is taken from `source(row, col)` of this one.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> Point) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| self[source(row, col)].clone())
+            .collect();
+        Self::new(width, height, cells)
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |row, col| (col, row))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |row, col| {
+            (self.height - col - 1, row)
+        })
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |row, col| {
+            (col, self.width - row - 1)
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Point) -> &T {
+        self.get(pos).expect("Point outside grid")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, pos: Point) -> &mut T {
+        self.get_mut(pos).expect("Point outside grid")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse(0, "abc\ndef", Ok).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.last(), (1, 2));
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn parse_error() {
+        let err =
+            Grid::parse(0, "12\n3x", |c| c.to_digit(10).ok_or("expected a digit")).unwrap_err();
+        assert_eq!(err, ParseError::new(0, 2, 2, "x", "expected a digit"));
+        let err = Grid::parse(0, "12\n345", Ok).unwrap_err();
+        assert_eq!(err.reason, "expected all rows to be the same width");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours4((0, 1)).collect::<Vec<_>>(),
+            vec![(0, 2), (1, 1), (0, 0)]
+        );
+        assert_eq!(
+            grid.neighbours8((1, 2)).collect::<Vec<_>>(),
+            vec![(0, 2), (1, 1), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours8((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = grid();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![['a', 'b', 'c'], ['d', 'e', 'f']]
+        );
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["ad", "be", "cf"]
+        );
+        assert_eq!(Grid::<char>::new(0, 0, vec![]).rows().count(), 0);
+    }
+
+    #[test]
+    fn transform() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_clockwise().to_string(),
+            "fed\ncba\n"
+        );
+    }
+}
@@ -3,6 +3,8 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+#[cfg(test)]
+mod answers;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -22,6 +24,11 @@ pub mod day16;
 pub mod day17;
 pub mod day18;
 pub mod day19;
+pub mod direction;
+pub mod geometry;
+pub mod grid;
 pub mod parser;
+pub mod runner;
+pub mod search;
 
 aoc_lib! { year = 2023 }
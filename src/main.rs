@@ -1,7 +1,72 @@
 extern crate aoc_23_rs;
-extern crate aoc_runner;
-extern crate aoc_runner_derive;
 
-use aoc_runner_derive::aoc_main;
+use std::{env, process};
 
-aoc_main! {lib = aoc_23_rs}
+use aoc_23_rs::{day17, parser::InputSource, runner};
+
+const USAGE: &str = "\
+Usage: aoc-23-rs [DAY] [--input DIR] [--year YEAR] [--file DAY=PATH]... [--stdin] [--svg DIR]
+
+Input defaults to <DIR>/<YEAR>/day<DAY>.txt where DIR is $AOC_INPUT_DIR or the crate's
+input directory and YEAR is $AOC_YEAR or 2023. $AOC_INPUT_DAY<N> or --file points day N at
+a specific file. --stdin reads a single day's input from stdin. --svg draws each part's
+route for day 17 into DIR.";
+
+fn main() {
+    let mut source = InputSource::from_env();
+    let mut day = None;
+    let mut stdin = false;
+    let mut svg = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let result = match arg.as_str() {
+            "--input" => args.next().map(|dir| source = source.clone().root(dir)),
+            "--year" => args
+                .next()
+                .and_then(|y| y.parse().ok())
+                .map(|year| source = source.clone().year(year)),
+            "--file" => args
+                .next()
+                .as_deref()
+                .and_then(|f| f.split_once('='))
+                .and_then(|(d, path)| Some((d.parse().ok()?, path.to_string())))
+                .map(|(d, path)| source = source.clone().with_file(d, path)),
+            "--svg" => args.next().map(|dir| svg = Some(dir)),
+            "--stdin" => {
+                stdin = true;
+                Some(())
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => arg.parse().ok().map(|d| day = Some(d)),
+        };
+        if result.is_none() {
+            eprintln!("Invalid argument: {}\n\n{}", arg, USAGE);
+            process::exit(2);
+        }
+    }
+
+    if stdin {
+        if day.is_none() {
+            eprintln!("--stdin needs a DAY\n\n{}", USAGE);
+            process::exit(2);
+        }
+        source = InputSource::stdin();
+    }
+
+    runner::run(&source, day);
+
+    if let Some(dir) = svg.filter(|_| day.is_none_or(|d| d == 17)) {
+        let written = source
+            .read(17)
+            .map_err(|e| e.into())
+            .and_then(|input| day17::write_routes(&input, &dir));
+        match written {
+            Ok(paths) => paths.iter().for_each(|p| println!("Wrote {}", p.display())),
+            Err(e) => eprintln!("Day 17: FAILED while drawing routes: {}", e),
+        }
+    }
+}
@@ -1,21 +1,243 @@
 use std::{
-    fs::{self, File},
-    io::{self, BufRead, BufReader},
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 
-pub fn load_input(day: usize) -> Vec<String> {
-    let path = format!("input/2023/day{}.txt", day);
-    let f: File = File::open(path).unwrap();
-    let reader: BufReader<File> = BufReader::new(f);
-    reader
+/// Describes where and why a day's puzzle input could not be parsed.
+/// Line and column numbers are 1-based and relative to the text handed to the generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(day: usize, line: usize, column: usize, token: &str, reason: &str) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            token: token.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} line {} column {}: {} (found {:?})",
+            self.day, self.line, self.column, self.reason, self.token
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// A single line of puzzle input which remembers where it came from, so that
+/// errors can point back at the offending token.
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub day: usize,
+    pub number: usize,
+    raw: &'a str,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Column of a token borrowed from this line. Tokens which don't come from
+    /// this line are reported at the start of the text.
+    pub fn column(&self, token: &str) -> usize {
+        let start = self.raw.as_ptr() as usize;
+        let pos = token.as_ptr() as usize;
+        if pos >= start && pos + token.len() <= start + self.raw.len() {
+            pos - start + 1
+        } else {
+            self.column(self.text)
+        }
+    }
+
+    pub fn error(&self, token: &str, reason: &str) -> ParseError {
+        ParseError::new(self.day, self.number, self.column(token), token, reason)
+    }
+
+    /// Error for the character starting at byte `index` of the text.
+    pub fn error_at(&self, index: usize, reason: &str) -> ParseError {
+        let len = self.text[index..].chars().next().map_or(0, char::len_utf8);
+        self.error(&self.text[index..index + len], reason)
+    }
+
+    /// Error for something which should have been on this line but wasn't.
+    pub fn missing(&self, reason: &str) -> ParseError {
+        self.error(&self.text[self.text.len()..], reason)
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &'a str, reason: &str) -> Result<T, ParseError> {
+        token.parse::<T>().map_err(|_| self.error(token, reason))
+    }
+}
+
+/// Split input into trimmed lines, ignoring any blank lines surrounding the input
+/// but keeping the line numbers of the original text.
+pub fn lines(day: usize, input: &str) -> impl Iterator<Item = Line<'_>> {
+    let is_blank = |l: &&str| l.trim().is_empty();
+    let first = input.lines().position(|l| !is_blank(&l)).unwrap_or(0);
+    let count = input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !is_blank(l))
+        .last()
+        .map_or(0, |(index, _)| index + 1 - first);
+    input
         .lines()
-        .collect::<io::Result<Vec<String>>>()
-        .expect("Unable to open file")
+        .enumerate()
+        .skip(first)
+        .take(count)
+        .map(move |(index, raw)| Line {
+            day,
+            number: index + 1,
+            raw,
+            text: raw.trim(),
+        })
+}
+
+/// Where puzzle input is read from. Per-day files take precedence over the origin, so a
+/// single day can be pointed at a different file without moving the rest.
+#[derive(Clone, Debug)]
+pub struct InputSource {
+    origin: Origin,
+    overrides: HashMap<usize, PathBuf>,
 }
 
-pub fn load_input_string(day: usize) -> String {
-    let path = format!("input/2023/day{}.txt", day);
-    fs::read_to_string(path).expect("Unable to open file")
+#[derive(Clone, Debug)]
+enum Origin {
+    /// Files laid out as `<root>/<year>/day<N>.txt`
+    Directory { root: PathBuf, year: u32 },
+    /// The whole of stdin is the input, whichever day is asked for
+    Stdin,
+    /// The same text is the input, whichever day is asked for
+    Memory(String),
+}
+
+impl InputSource {
+    pub const DIR_VAR: &'static str = "AOC_INPUT_DIR";
+    pub const YEAR_VAR: &'static str = "AOC_YEAR";
+    pub const DAY_VAR_PREFIX: &'static str = "AOC_INPUT_DAY";
+    pub const DEFAULT_YEAR: u32 = 2023;
+
+    pub fn directory(root: impl Into<PathBuf>) -> Self {
+        Self::from_origin(Origin::Directory {
+            root: root.into(),
+            year: Self::DEFAULT_YEAR,
+        })
+    }
+
+    pub fn stdin() -> Self {
+        Self::from_origin(Origin::Stdin)
+    }
+
+    pub fn memory(input: &str) -> Self {
+        Self::from_origin(Origin::Memory(input.to_string()))
+    }
+
+    /// The `input` directory of this crate, regardless of the working directory.
+    pub fn repository() -> Self {
+        Self::directory(Path::new(env!("CARGO_MANIFEST_DIR")).join("input"))
+    }
+
+    /// Start from [`InputSource::repository`] and apply any overrides found in the environment:
+    /// `AOC_INPUT_DIR` replaces the root, `AOC_YEAR` the year and `AOC_INPUT_DAY<N>` points
+    /// day N at a specific file.
+    pub fn from_env() -> Self {
+        let mut source = match env::var_os(Self::DIR_VAR) {
+            Some(root) => Self::directory(root),
+            None => Self::repository(),
+        };
+        if let Some(year) = env::var(Self::YEAR_VAR).ok().and_then(|y| y.parse().ok()) {
+            source = source.year(year);
+        }
+        for (key, path) in env::vars_os() {
+            let day = key
+                .to_str()
+                .and_then(|k| k.strip_prefix(Self::DAY_VAR_PREFIX))
+                .and_then(|d| d.parse::<usize>().ok());
+            if let Some(day) = day {
+                source = source.with_file(day, path);
+            }
+        }
+        source
+    }
+
+    fn from_origin(origin: Origin) -> Self {
+        Self {
+            origin,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Change the year of a directory source. Other sources have no notion of year.
+    pub fn year(mut self, year: u32) -> Self {
+        if let Origin::Directory { year: y, .. } = &mut self.origin {
+            *y = year;
+        }
+        self
+    }
+
+    /// Read from files under `root`, keeping the year and any per-day files already set.
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        match &mut self.origin {
+            Origin::Directory { root: r, .. } => *r = root.into(),
+            origin => {
+                *origin = Origin::Directory {
+                    root: root.into(),
+                    year: Self::DEFAULT_YEAR,
+                }
+            }
+        }
+        self
+    }
+
+    pub fn with_file(mut self, day: usize, path: impl Into<PathBuf>) -> Self {
+        self.overrides.insert(day, path.into());
+        self
+    }
+
+    /// The file a day's input would be read from, if it comes from a file at all.
+    pub fn path(&self, day: usize) -> Option<PathBuf> {
+        if let Some(path) = self.overrides.get(&day) {
+            return Some(path.clone());
+        }
+        match &self.origin {
+            Origin::Directory { root, year } => {
+                Some(root.join(year.to_string()).join(format!("day{}.txt", day)))
+            }
+            Origin::Stdin | Origin::Memory(_) => None,
+        }
+    }
+
+    pub fn read(&self, day: usize) -> io::Result<String> {
+        if let Some(path) = self.path(day) {
+            return fs::read_to_string(&path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)));
+        }
+        match &self.origin {
+            Origin::Stdin => io::read_to_string(io::stdin()),
+            Origin::Memory(input) => Ok(input.clone()),
+            Origin::Directory { .. } => unreachable!("directory sources always have a path"),
+        }
+    }
+
+    pub fn read_lines(&self, day: usize) -> io::Result<Vec<String>> {
+        Ok(self.read(day)?.lines().map(|l| l.to_string()).collect())
+    }
 }
 
 pub fn test_input(input: &str) -> Vec<String> {
@@ -25,3 +247,59 @@ pub fn test_input(input: &str) -> Vec<String> {
         .map(|l| l.trim().to_string())
         .collect::<Vec<String>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_keep_original_numbering() {
+        let lines = lines(1, "\n\n  abc\n\n  def 12\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!((lines[0].number, lines[0].text), (3, "abc"));
+        assert_eq!((lines[2].number, lines[2].text), (5, "def 12"));
+    }
+
+    #[test]
+    fn error_points_at_token() {
+        let line = lines(4, "  def x2").next().unwrap();
+        let token = line.text.split_whitespace().last().unwrap();
+        let err = line.parse::<u32>(token, "expected a number").unwrap_err();
+        assert_eq!(err, ParseError::new(4, 1, 7, "x2", "expected a number"));
+        assert_eq!(
+            err.to_string(),
+            "day 4 line 1 column 7: expected a number (found \"x2\")"
+        );
+    }
+
+    #[test]
+    fn input_source_paths() {
+        let source = InputSource::directory("/inputs").year(2022);
+        assert_eq!(source.path(3), Some(PathBuf::from("/inputs/2022/day3.txt")));
+        let source = source.with_file(3, "/elsewhere/three.txt");
+        assert_eq!(source.path(3), Some(PathBuf::from("/elsewhere/three.txt")));
+        assert_eq!(source.path(4), Some(PathBuf::from("/inputs/2022/day4.txt")));
+
+        let source = source.root("/moved");
+        assert_eq!(source.path(3), Some(PathBuf::from("/elsewhere/three.txt")));
+        assert_eq!(source.path(4), Some(PathBuf::from("/moved/2022/day4.txt")));
+    }
+
+    #[test]
+    fn input_source_memory() {
+        let source = InputSource::memory("1abc2\npqr3stu8vwx");
+        assert_eq!(source.path(1), None);
+        assert_eq!(source.read_lines(1).unwrap(), vec!["1abc2", "pqr3stu8vwx"]);
+        assert!(InputSource::memory("")
+            .with_file(1, "/no/such/file")
+            .read(1)
+            .is_err());
+    }
+
+    #[test]
+    fn repository_source_ignores_working_directory() {
+        let path = InputSource::repository().path(1).unwrap();
+        assert!(path.is_absolute());
+        assert!(path.ends_with("input/2023/day1.txt"));
+    }
+}
@@ -0,0 +1,115 @@
+use std::{error::Error, fmt::Display, time::Instant};
+
+use aoc_runner::{ArcStr, Runner};
+
+use crate::{parser::InputSource, *};
+
+type Generator = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// A day and part which can be solved, backed by the generator and solver registered with `#[aoc]`.
+pub struct Solver {
+    pub day: usize,
+    pub part: usize,
+    generator: Generator,
+}
+
+impl Solver {
+    pub fn generate(&self, input: &str) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+        (self.generator)(ArcStr::from(input))
+    }
+
+    pub fn solve(&self, input: &str) -> Result<Box<dyn Display>, Box<dyn Error>> {
+        self.generate(input)?.try_run()
+    }
+}
+
+macro_rules! solvers {
+    ($($day:literal: $($part:literal => $t:ident::$f:ident),+;)+) => {
+        pub static SOLVERS: &[Solver] = &[
+            $($(Solver { day: $day, part: $part, generator: <Factory as $t>::$f },)+)+
+        ];
+    };
+}
+
+solvers! {
+    1: 1 => Day1Part1::day1_part1, 2 => Day1Part2::day1_part2;
+    2: 1 => Day2Part1::day2_part1, 2 => Day2Part2::day2_part2;
+    3: 1 => Day3Part1::day3_part1, 2 => Day3Part2::day3_part2;
+    4: 1 => Day4Part1::day4_part1, 2 => Day4Part2::day4_part2;
+    5: 1 => Day5Part1::day5_part1, 2 => Day5Part2::day5_part2;
+    6: 1 => Day6Part1::day6_part1, 2 => Day6Part2::day6_part2;
+    7: 1 => Day7Part1::day7_part1, 2 => Day7Part2::day7_part2;
+    8: 1 => Day8Part1::day8_part1, 2 => Day8Part2::day8_part2;
+    9: 1 => Day9Part1::day9_part1, 2 => Day9Part2::day9_part2;
+    10: 1 => Day10Part1::day10_part1, 2 => Day10Part2::day10_part2;
+    11: 1 => Day11Part1::day11_part1, 2 => Day11Part2::day11_part2;
+    12: 1 => Day12Part1::day12_part1, 2 => Day12Part2::day12_part2;
+    13: 1 => Day13Part1::day13_part1, 2 => Day13Part2::day13_part2;
+    14: 1 => Day14Part1::day14_part1, 2 => Day14Part2::day14_part2;
+    15: 1 => Day15Part1::day15_part1, 2 => Day15Part2::day15_part2;
+    16: 1 => Day16Part1::day16_part1, 2 => Day16Part2::day16_part2;
+    17: 1 => Day17Part1::day17_part1, 2 => Day17Part2::day17_part2;
+    18: 1 => Day18Part1::day18_part1, 2 => Day18Part2::day18_part2;
+    19: 1 => Day19Part1::day19_part1, 2 => Day19Part2::day19_part2;
+}
+
+/// Solve every registered day (or just `day`) with input from `source`, printing each answer
+/// alongside how long the generator and solver took. Each day's input is read once.
+pub fn run(source: &InputSource, day: Option<usize>) {
+    println!("Advent of code {}", crate::YEAR);
+
+    let mut days = SOLVERS.iter().map(|s| s.day).collect::<Vec<usize>>();
+    days.dedup();
+
+    for d in days.into_iter().filter(|d| day.is_none_or(|day| day == *d)) {
+        let input = match source.read(d) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Day {}: FAILED while reading input: {}", d, e);
+                continue;
+            }
+        };
+
+        for solver in SOLVERS.iter().filter(|s| s.day == d) {
+            let start_time = Instant::now();
+            let runner = match solver.generate(&input) {
+                Ok(runner) => runner,
+                Err(e) => {
+                    eprintln!(
+                        "Day {} - Part {}: FAILED while generating: {}",
+                        solver.day, solver.part, e
+                    );
+                    continue;
+                }
+            };
+            let inter_time = Instant::now();
+            match runner.try_run() {
+                Ok(result) => println!(
+                    "Day {} - Part {}: {}\n\tgenerator: {:?},\n\trunner: {:?}\n",
+                    solver.day,
+                    solver.part,
+                    result,
+                    inter_time - start_time,
+                    Instant::now() - inter_time
+                ),
+                Err(e) => eprintln!(
+                    "Day {} - Part {}: FAILED while running: {}",
+                    solver.day, solver.part, e
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solver_uses_registered_generator() {
+        let solver = SOLVERS.iter().find(|s| s.day == 9 && s.part == 1).unwrap();
+        let answer = solver.solve("0 3 6 9 12 15\n1 3 6 10 15 21").unwrap();
+        assert_eq!(answer.to_string(), "46");
+        assert!(solver.solve("0 3 x").is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap},
+    hash::Hash,
+    ops::Add,
+    time::{Duration, Instant},
+};
+
+/// The cheapest route found to a goal, with the states visited from the start to the goal inclusive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    pub states: Vec<S>,
+}
+
+/// How much work a search did to find its answer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// States taken off the OPEN list and had their successors generated
+    pub expanded: usize,
+    /// Entries added to the OPEN list, including the start
+    pub pushed: usize,
+    /// Largest size the OPEN list reached
+    pub max_open: usize,
+    pub elapsed: Duration,
+}
+
+/// Find the cheapest path from `start` to any state satisfying `goal`, guided by `heuristic`
+/// ([A* search](https://en.wikipedia.org/wiki/A*_search_algorithm)).
+///
+/// `successors` returns each state reachable from the given one along with the cost of the
+/// move. The heuristic must never overestimate the remaining cost, otherwise the path found
+/// may not be the cheapest.
+pub fn a_star<S, C, N, I, G, H>(
+    start: S,
+    successors: N,
+    goal: G,
+    heuristic: H,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> C,
+{
+    a_star_with_stats(start, successors, goal, heuristic).0
+}
+
+/// [`a_star`], also reporting how much work the search did.
+pub fn a_star_with_stats<S, C, N, I, G, H>(
+    start: S,
+    mut successors: N,
+    mut goal: G,
+    mut heuristic: H,
+) -> (Option<Path<S, C>>, Stats)
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> C,
+{
+    let timer = Instant::now();
+    let mut stats = Stats {
+        pushed: 1,
+        max_open: 1,
+        ..Stats::default()
+    };
+
+    // Every state reached so far, the cheapest known cost to reach it and where it was reached from
+    let mut states = vec![start.clone()];
+    let mut best = vec![(C::default(), None)];
+    let mut indices = HashMap::from([(start.clone(), 0)]);
+
+    // OPEN list is a min-heap with lowest f at the top
+    let mut open = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+
+    while let Some(Reverse((_, g, index))) = open.pop() {
+        // Skip entries superseded by a cheaper route to the same state
+        if g > best[index].0 {
+            continue;
+        }
+
+        if goal(&states[index]) {
+            let path = Path {
+                cost: g,
+                states: reconstruct(&states, &best, index),
+            };
+            stats.elapsed = timer.elapsed();
+            return (Some(path), stats);
+        }
+
+        stats.expanded += 1;
+        for (next, cost) in successors(&states[index]) {
+            let next_g = g + cost;
+            let next_index = match indices.entry(next) {
+                Entry::Occupied(entry) => {
+                    let next_index = *entry.get();
+                    if best[next_index].0 <= next_g {
+                        continue;
+                    }
+                    best[next_index] = (next_g, Some(index));
+                    next_index
+                }
+                Entry::Vacant(entry) => {
+                    states.push(entry.key().clone());
+                    best.push((next_g, Some(index)));
+                    *entry.insert(states.len() - 1)
+                }
+            };
+            let f = next_g + heuristic(&states[next_index]);
+            open.push(Reverse((f, next_g, next_index)));
+            stats.pushed += 1;
+        }
+        stats.max_open = stats.max_open.max(open.len());
+    }
+
+    stats.elapsed = timer.elapsed();
+    (None, stats)
+}
+
+/// [Dijkstra's algorithm](https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm), or A* without a heuristic.
+pub fn dijkstra<S, C, N, I, G>(start: S, successors: N, goal: G) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    a_star(start, successors, goal, |_| C::default())
+}
+
+/// The cheapest cost from `start` to every state reachable from it.
+pub fn costs<S, C, N, I>(start: S, mut successors: N) -> HashMap<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut costs = HashMap::new();
+    let mut states = vec![start];
+    let mut open = BinaryHeap::from([Reverse((C::default(), 0))]);
+
+    while let Some(Reverse((g, index))) = open.pop() {
+        let state = &states[index];
+        if costs.contains_key(state) {
+            continue;
+        }
+        for (next, cost) in successors(state) {
+            if !costs.contains_key(&next) {
+                open.push(Reverse((g + cost, states.len())));
+                states.push(next);
+            }
+        }
+        costs.insert(states[index].clone(), g);
+    }
+
+    costs
+}
+
+/// Follow the parents back from `index` to the start.
+fn reconstruct<S: Clone, C>(states: &[S], best: &[(C, Option<usize>)], index: usize) -> Vec<S> {
+    let mut path = vec![states[index].clone()];
+    let mut current = index;
+    while let Some(parent) = best[current].1 {
+        path.push(states[parent].clone());
+        current = parent;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walk along a number line where each step right costs 1 and each jump of 3 costs 2.
+    fn successors(n: &u32) -> Vec<(u32, u32)> {
+        vec![(n + 1, 1), (n + 3, 2)]
+    }
+
+    #[test]
+    fn cheapest_path() {
+        let path = dijkstra(0, successors, |&n| n == 7).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.states.first(), Some(&0));
+        assert_eq!(path.states.last(), Some(&7));
+        assert_eq!(path.states.len(), 4);
+
+        let (guided, stats) =
+            a_star_with_stats(0, successors, |&n| n == 7, |&n| 7u32.saturating_sub(n) / 3);
+        assert_eq!(guided.unwrap().cost, 5);
+        assert!(stats.expanded > 0);
+        assert!(stats.pushed >= stats.max_open);
+    }
+
+    #[test]
+    fn start_is_goal() {
+        let path = dijkstra(4, successors, |&n| n == 4).unwrap();
+        assert_eq!(
+            path,
+            Path {
+                cost: 0,
+                states: vec![4]
+            }
+        );
+    }
+
+    #[test]
+    fn costs_to_every_state() {
+        let costs = costs(0, |&n: &u32| {
+            successors(&n).into_iter().filter(|&(n, _)| n <= 6)
+        });
+        assert_eq!(costs.len(), 7);
+        assert_eq!(costs[&0], 0);
+        assert_eq!(costs[&4], 3);
+        assert_eq!(costs[&6], 4);
+    }
+
+    #[test]
+    fn unreachable() {
+        let bounded = |n: &u32| successors(n).into_iter().filter(|&(n, _)| n < 10);
+        assert_eq!(dijkstra(0, bounded, |&n| n == 12), None);
+    }
+}